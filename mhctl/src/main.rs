@@ -0,0 +1,184 @@
+mod output;
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use minehut_api::blocking::MinehutClient;
+use minehut_api::prelude::{
+    Environment, MinehutClientBuilder, MinehutError, ServerListFilter, ServerListing,
+};
+use output::{Format, Table};
+use serde_json::{json, Value};
+use std::process::ExitCode;
+
+/// Looks things up from Minehut's API.
+#[derive(Parser, Debug)]
+#[command(name = "mhctl", version)]
+struct Cli {
+    /// Which api to call: `prod`, `dev` or a base url, ex: `http://127.0.0.1:8080`.
+    ///
+    /// Falls back to the `MINEHUT_URL` environment variable, then the dev api.
+    #[arg(long, global = true)]
+    env: Option<Environment>,
+    #[arg(long, short, global = true, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Network wide player, server and ram counts.
+    Stats,
+    /// Looks up one server by name.
+    Server {
+        server: String,
+        /// Look the server up by its `_id` instead.
+        #[arg(long)]
+        id: bool,
+    },
+    /// Checks whether no server is called `name` yet.
+    Available { name: String },
+    /// Sends a GET to any api path, ex: `/network/simple_stats`.
+    Get { path: String },
+    /// Lists online servers, optionally filtered.
+    List {
+        /// Only servers whose name or MOTD contains this, ignoring case.
+        search: Option<String>,
+        #[arg(long)]
+        category: Option<String>,
+        #[arg(long)]
+        platform: Option<String>,
+        #[arg(long)]
+        version_type: Option<String>,
+        #[arg(long, default_value_t = 0)]
+        min_players: usize,
+        /// The most servers to print.
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> anyhow::Result<()> {
+    let client = MinehutClient::new(
+        MinehutClientBuilder::default()
+            .environment(cli.env.unwrap_or_else(Environment::from_env))
+            .build()?,
+    );
+
+    match cli.command {
+        Command::Stats => {
+            let stats = serde_json::to_value(client.get_simple_stats()?)?;
+            output::print(cli.format, &stats, &Table::from_value(&stats))
+        }
+        Command::Server { server, id } => {
+            let server = if id {
+                client.get_server_by_id(server)?
+            } else {
+                match client.get_server_by_name(&server) {
+                    Err(err @ MinehutError::NotFound { .. }) => {
+                        let suggestions = client
+                            .search_servers(&server, 3)
+                            .unwrap_or_default()
+                            .iter()
+                            .map(|candidate| candidate.name().to_string())
+                            .collect::<Vec<_>>();
+                        if suggestions.is_empty() {
+                            return Err(err.into());
+                        }
+                        anyhow::bail!("{err} Did you mean {}?", suggestions.join(", "));
+                    }
+                    response => response?,
+                }
+            };
+            let typed = serde_json::to_value(server.typed())?;
+            let raw = serde_json::to_value(server)?;
+            output::print(cli.format, &raw, &Table::from_value(&typed))
+        }
+        Command::Available { name } => {
+            let available = json!({
+                "name": name,
+                "available": client.is_name_available(&name)?,
+            });
+            output::print(cli.format, &available, &Table::from_value(&available))
+        }
+        Command::Get { path } => {
+            let payload = client
+                .call(&path)
+                .get::<Value>()
+                .with_context(|| format!("Failed to GET {path}."))?;
+            output::print(cli.format, &payload, &Table::from_value(&payload))
+        }
+        Command::List {
+            search,
+            category,
+            platform,
+            version_type,
+            min_players,
+            limit,
+        } => {
+            let mut filter = ServerListFilter::default().min_players(min_players);
+            if let Some(category) = category {
+                filter = filter.category(category);
+            }
+            if let Some(platform) = platform {
+                filter = filter.platform(platform);
+            }
+            if let Some(version_type) = version_type {
+                filter = filter.version_type(version_type);
+            }
+            let search = search.map(|search| search.to_lowercase());
+            let matches_search = |server: &ServerListing| match &search {
+                None => true,
+                Some(search) => [&server.name, &server.motd]
+                    .into_iter()
+                    .flatten()
+                    .any(|text| text.to_lowercase().contains(search)),
+            };
+
+            let servers = client
+                .list_servers(filter)
+                .filter(|server| server.as_ref().map_or(true, matches_search))
+                .take(limit.unwrap_or(usize::MAX))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut table = Table::new([
+                "name",
+                "players",
+                "max_players",
+                "platform",
+                "version_type",
+                "categories",
+            ]);
+            for server in &servers {
+                table.row([
+                    server.name.clone().unwrap_or_default(),
+                    server.player_count().to_string(),
+                    server
+                        .max_players
+                        .map(|max| max.to_string())
+                        .unwrap_or_default(),
+                    server.platform().unwrap_or_default().to_string(),
+                    server.server_version_type().unwrap_or_default().to_string(),
+                    server
+                        .all_categories
+                        .iter()
+                        .flatten()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ]);
+            }
+            output::print(cli.format, &serde_json::to_value(&servers)?, &table)
+        }
+    }
+}
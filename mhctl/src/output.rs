@@ -0,0 +1,250 @@
+use serde_json::Value;
+use std::io::Write;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum Format {
+    /// Aligned columns for reading in a terminal.
+    Table,
+    /// The full payload as JSON.
+    Json,
+    Csv,
+}
+
+/// Rows of cells, as printed by [`Format::Table`] and [`Format::Csv`].
+#[derive(Debug, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(headers: I) -> Self {
+        Self {
+            headers: headers.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row<I: IntoIterator<Item = S>, S: Into<String>>(&mut self, row: I) {
+        self.rows.push(row.into_iter().map(Into::into).collect());
+    }
+
+    /// An object becomes `field, value` rows, an array of objects becomes one row per object
+    /// with a column for every field seen.
+    ///
+    /// Wrapped responses like `{"plans": [...]}` are rendered as the array they wrap.
+    pub fn from_value(value: &Value) -> Self {
+        match value {
+            Value::Object(fields) => match fields.values().next() {
+                Some(inner @ Value::Array(_)) if fields.len() == 1 => Table::from_value(inner),
+                _ => {
+                    let mut table = Table::new(["field", "value"]);
+                    for (field, value) in fields {
+                        table.row([field.clone(), cell(value)]);
+                    }
+                    table
+                }
+            },
+            Value::Array(items) if items.iter().all(Value::is_object) => {
+                let mut headers = Vec::<String>::new();
+                for item in items.iter().filter_map(Value::as_object) {
+                    for field in item.keys() {
+                        if !headers.contains(field) {
+                            headers.push(field.clone());
+                        }
+                    }
+                }
+                let mut table = Table::new(headers.clone());
+                for item in items {
+                    table.row(headers.iter().map(|field| cell(&item[field])));
+                }
+                table
+            }
+            Value::Array(items) => {
+                let mut table = Table::new(["value"]);
+                for item in items {
+                    table.row([cell(item)]);
+                }
+                table
+            }
+            value => {
+                let mut table = Table::new(["value"]);
+                table.row([cell(value)]);
+                table
+            }
+        }
+    }
+
+    fn write_aligned<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        let mut widths = self
+            .headers
+            .iter()
+            .map(|header| header.chars().count())
+            .collect::<Vec<_>>();
+        for row in &self.rows {
+            for (column, value) in row.iter().enumerate() {
+                let width = value.chars().count();
+                match widths.get_mut(column) {
+                    Some(existing) => *existing = (*existing).max(width),
+                    None => widths.push(width),
+                }
+            }
+        }
+
+        let write_row = |out: &mut W, row: &[String]| -> std::io::Result<()> {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(value, width)| format!("{value:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(out, "{}", line.trim_end())
+        };
+        write_row(out, &self.headers)?;
+        let rule = widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>();
+        write_row(out, &rule)?;
+        for row in &self.rows {
+            write_row(out, row)?;
+        }
+        Ok(())
+    }
+
+    fn write_csv<W: Write>(&self, out: W) -> anyhow::Result<()> {
+        let mut writer = csv::Writer::from_writer(out);
+        writer.write_record(&self.headers)?;
+        for row in &self.rows {
+            writer.write_record(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Prints `json` as is for [`Format::Json`], otherwise prints `table`.
+pub fn print(format: Format, json: &Value, table: &Table) -> anyhow::Result<()> {
+    let mut out = std::io::stdout().lock();
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut out, json)?;
+            writeln!(out)?;
+        }
+        Format::Table => table.write_aligned(&mut out)?,
+        Format::Csv => table.write_csv(out)?,
+    }
+    Ok(())
+}
+
+/// A single line rendering of `value`, nested objects stay JSON.
+pub fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => value.clone(),
+        Value::Array(items) if !items.iter().any(|item| item.is_object() || item.is_array()) => {
+            items.iter().map(cell).collect::<Vec<_>>().join(", ")
+        }
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn csv(table: &Table) -> String {
+        let mut out = Vec::new();
+        table.write_csv(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn aligned(table: &Table) -> String {
+        let mut out = Vec::new();
+        table.write_aligned(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn objects_become_field_rows() {
+        let table = Table::from_value(&json!({
+            "name": "MockHub",
+            "online": true,
+            "categories": ["pvp", "minigames"],
+            "playerData": { "playerCount": 3 },
+            "motd": null,
+        }));
+        assert_eq!(table.headers, ["field", "value"]);
+        assert_eq!(
+            table.rows,
+            [
+                ["categories", "pvp, minigames"],
+                ["motd", ""],
+                ["name", "MockHub"],
+                ["online", "true"],
+                ["playerData", r#"{"playerCount":3}"#],
+            ]
+        );
+    }
+
+    #[test]
+    fn arrays_of_objects_get_a_column_for_every_field() {
+        let table = Table::from_value(&json!({
+            "servers": [
+                { "name": "MockHub", "playerCount": 3 },
+                { "name": "MockSurvival", "motd": "Hi", "staticInfo": { "_id": "5f1a" } },
+            ]
+        }));
+        assert_eq!(table.headers, ["name", "playerCount", "motd", "staticInfo"]);
+        assert_eq!(
+            table.rows,
+            [
+                ["MockHub", "3", "", ""],
+                ["MockSurvival", "", "Hi", r#"{"_id":"5f1a"}"#],
+            ]
+        );
+    }
+
+    #[test]
+    fn wrappers_with_other_fields_stay_objects() {
+        let table = Table::from_value(&json!({ "servers": [], "total_servers": 0 }));
+        assert_eq!(table.headers, ["field", "value"]);
+        assert_eq!(table.rows.len(), 2);
+    }
+
+    #[test]
+    fn scalars_and_mixed_arrays_are_single_values() {
+        let table = Table::from_value(&json!([1, { "a": 1 }, [2, 3]]));
+        assert_eq!(table.headers, ["value"]);
+        assert_eq!(table.rows, [["1"], [r#"{"a":1}"#], ["2, 3"]]);
+
+        let table = Table::from_value(&json!("MockHub"));
+        assert_eq!(table.rows, [["MockHub"]]);
+    }
+
+    #[test]
+    fn csv_quotes_commas_quotes_and_newlines() {
+        let mut table = Table::new(["name", "motd"]);
+        table.row(["MockHub", r#"Welcome, "friend""#]);
+        table.row(["MockSurvival", "Two\nlines"]);
+        assert_eq!(
+            csv(&table),
+            "name,motd\nMockHub,\"Welcome, \"\"friend\"\"\"\nMockSurvival,\"Two\nlines\"\n"
+        );
+    }
+
+    #[test]
+    fn columns_are_aligned_to_the_widest_cell() {
+        let mut table = Table::new(["name", "players"]);
+        table.row(["MockSurvival", "12"]);
+        table.row(["MockHub", "3"]);
+        assert_eq!(
+            aligned(&table),
+            "name          players\n\
+             ------------  -------\n\
+             MockSurvival  12\n\
+             MockHub       3\n"
+        );
+    }
+}
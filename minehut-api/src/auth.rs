@@ -0,0 +1,246 @@
+//! Logged in access to Minehut's API, for managing servers we own.
+//!
+//! A [`Session`] logs in lazily on the first call which needs it, sends its token with every call
+//! made through the client, and logs in again once the token expires or Minehut rejects it.
+
+use crate::client::MinehutClient;
+use crate::error::Result;
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const SESSION_ID_HEADER: &str = "x-session-id";
+/// Query parameters whose values never make it into logs.
+const SECRET_PARAMS: [&str; 5] = ["token", "password", "session", "secret", "key"];
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone)]
+pub struct Credentials {
+    pub email: String,
+    pub password: String,
+}
+
+impl Credentials {
+    pub fn new<E: Into<String>, P: Into<String>>(email: E, password: P) -> Self {
+        Self {
+            email: email.into(),
+            password: password.into(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("email", &self.email)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone)]
+pub struct LoginResponse {
+    pub token: String,
+    #[serde(rename = "_id")]
+    pub user_id: Option<String>,
+    pub session_id: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl std::fmt::Debug for LoginResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoginResponse")
+            .field("token", &"<redacted>")
+            .field("user_id", &self.user_id)
+            .field("session_id", &"<redacted>")
+            .finish_non_exhaustive()
+    }
+}
+
+/// The token sent with every authenticated call.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SessionToken {
+    pub token: String,
+    pub session_id: Option<String>,
+    pub user_id: Option<String>,
+    /// When the token should be replaced, `None` if it is only replaced once Minehut rejects it.
+    pub expires_at: Option<Instant>,
+}
+
+impl SessionToken {
+    pub fn new<S: Into<String>>(token: S) -> Self {
+        Self {
+            token: token.into(),
+            session_id: None,
+            user_id: None,
+            expires_at: None,
+        }
+    }
+
+    pub fn session_id<S: Into<String>>(mut self, session_id: S) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    pub fn user_id<S: Into<String>>(mut self, user_id: S) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    pub fn expires_in(mut self, lifetime: Duration) -> Self {
+        self.expires_at = Some(Instant::now() + lifetime);
+        self
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| Instant::now() >= expires_at)
+    }
+
+    pub(crate) fn headers(&self) -> Vec<(HeaderName, HeaderValue)> {
+        let mut headers = Vec::new();
+        if let Ok(mut token) = HeaderValue::from_str(&self.token) {
+            token.set_sensitive(true);
+            headers.push((AUTHORIZATION, token));
+        }
+        if let Some(Ok(mut session_id)) = self.session_id.as_deref().map(HeaderValue::from_str) {
+            session_id.set_sensitive(true);
+            headers.push((HeaderName::from_static(SESSION_ID_HEADER), session_id));
+        }
+        headers
+    }
+}
+
+impl std::fmt::Debug for SessionToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionToken")
+            .field("token", &"<redacted>")
+            .field(
+                "session_id",
+                &self.session_id.as_ref().map(|_| "<redacted>"),
+            )
+            .field("user_id", &self.user_id)
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct Session {
+    credentials: Option<Credentials>,
+    lifetime: Option<Duration>,
+    token: tokio::sync::Mutex<Option<SessionToken>>,
+}
+
+impl Session {
+    /// Logs in with `credentials` on the first authenticated call, and again whenever needed.
+    pub fn with_credentials(credentials: Credentials) -> Self {
+        Self {
+            credentials: Some(credentials),
+            lifetime: None,
+            token: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Uses an existing token, calls fail with [`MinehutError::Unauthorized`] once it is rejected.
+    ///
+    /// [`MinehutError::Unauthorized`]: crate::error::MinehutError::Unauthorized
+    pub fn with_token(token: SessionToken) -> Self {
+        Self {
+            credentials: None,
+            lifetime: None,
+            token: tokio::sync::Mutex::new(Some(token)),
+        }
+    }
+
+    /// Logs in again this long after each login instead of waiting for Minehut to reject the
+    /// token.
+    pub fn lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = Some(lifetime);
+        self
+    }
+
+    /// The token currently in use, if logged in.
+    pub async fn token(&self) -> Option<SessionToken> {
+        self.token.lock().await.clone()
+    }
+
+    /// The token to send, logging in first if there is none or it expired.
+    pub(crate) async fn current(&self, client: &MinehutClient) -> Result<Option<SessionToken>> {
+        let mut token = self.token.lock().await;
+        let needs_login = token.as_ref().is_none_or(SessionToken::is_expired);
+        if let (true, Some(credentials)) = (needs_login, &self.credentials) {
+            *token = Some(self.login(client, credentials).await?);
+        }
+        Ok(token.clone())
+    }
+
+    /// Logs in again after `rejected` was refused, unless another call already did.
+    ///
+    /// Returns whether there is a different token worth retrying with.
+    pub(crate) async fn refresh(
+        &self,
+        client: &MinehutClient,
+        rejected: Option<&SessionToken>,
+    ) -> Result<bool> {
+        let mut token = self.token.lock().await;
+        if token.as_ref() != rejected {
+            return Ok(token.is_some());
+        }
+        let Some(credentials) = &self.credentials else {
+            return Ok(false);
+        };
+        *token = Some(self.login(client, credentials).await?);
+        Ok(true)
+    }
+
+    async fn login(
+        &self,
+        client: &MinehutClient,
+        credentials: &Credentials,
+    ) -> Result<SessionToken> {
+        log::debug!(target: "MinehutAPI", "Logging in as {}", credentials.email);
+        let call = client.anonymous().call("/users/login").json(credentials);
+        // boxed since every call may log in first, which is itself a call
+        let response = Box::pin(call.post::<LoginResponse>()).await?;
+
+        let mut token = SessionToken::new(response.token);
+        token.session_id = response.session_id;
+        token.user_id = response.user_id;
+        if let Some(lifetime) = self.lifetime {
+            token = token.expires_in(lifetime);
+        }
+        Ok(token)
+    }
+}
+
+/// Masks the values of secret looking query parameters, ex: `?token=abc` becomes `?token=***`.
+pub(crate) fn redact(url: &str) -> Cow<'_, str> {
+    let Some((base, query)) = url.split_once('?') else {
+        return Cow::Borrowed(url);
+    };
+    if !query.split('&').any(is_secret) {
+        return Cow::Borrowed(url);
+    }
+
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_secret(pair) => format!("{name}=***"),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    Cow::Owned(format!("{base}?{query}"))
+}
+
+fn is_secret(pair: &str) -> bool {
+    is_secret_name(pair.split('=').next().unwrap_or_default())
+}
+
+/// Whether a parameter, header or field called `name` holds a secret, ex: `session_id`.
+pub(crate) fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_PARAMS.iter().any(|secret| name.contains(secret))
+}
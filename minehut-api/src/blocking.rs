@@ -0,0 +1,433 @@
+//! A synchronous [`MinehutClient`] for scripts and tools, enabled by the `blocking` feature.
+//!
+//! Every client runs its calls on its own tokio runtime with one worker thread, shared by its
+//! clones. The worker keeps pooled connections alive between calls.
+//!
+//! Blocking calls panic when made from inside an async runtime, use the async client there.
+//! Dropping a client there is fine, its runtime shuts down without waiting for its worker.
+//!
+//! [`MinehutClient`]: crate::client::MinehutClient
+
+use crate::catalog::{Catalog, Category, Icon, Plan};
+use crate::client;
+use crate::error::Result;
+use crate::listing::{ServerListFilter, ServerListResponse, ServerListing};
+use crate::manage::{self, ServerChange, ServerData, ServerProperties, ServerStatus};
+use crate::rest::{
+    self, ApiResponse, Method, NetworkSimpleStatsResponse, ServerByNameResponse, ServerRef,
+};
+use crate::retry::RetryPolicy;
+use crate::search::SearchMatch;
+use crate::user::UserProfile;
+use crate::watch::WatchEvent;
+use futures::stream::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::future::Future;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// A blocking handle to Minehut's API, wrapping an async [`client::MinehutClient`].
+///
+/// Cloning is cheap; every clone shares the same connection pool and runtime.
+#[derive(Clone, Debug)]
+pub struct MinehutClient {
+    client: client::MinehutClient,
+    runtime: Arc<ClientRuntime>,
+}
+
+impl MinehutClient {
+    /// Wraps `client`, everything it was built with, ex: a cache or session, still applies.
+    ///
+    /// # Panics
+    ///
+    /// If the runtime can't be started.
+    pub fn new(client: client::MinehutClient) -> Self {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("Failed to start the blocking Minehut client's runtime.");
+        Self {
+            client,
+            runtime: Arc::new(ClientRuntime(Some(runtime))),
+        }
+    }
+
+    /// Builds a client against the environment named by `MINEHUT_URL`, falling back to the dev api.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self::new(client::MinehutClient::from_env()?))
+    }
+
+    /// The async client every call goes through.
+    pub fn inner(&self) -> &client::MinehutClient {
+        &self.client
+    }
+
+    pub fn call<S: Into<String>>(&self, path: S) -> Call {
+        Call {
+            call: self.client.call(path),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Runs `future` to completion on this client's runtime.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Polls `stream` one item at a time, ex: a watch built with
+    /// `client.inner().watch_server(name, interval).changes_only()`.
+    pub fn iter<S: Stream + Send + 'static>(&self, stream: S) -> Iter<S::Item> {
+        Iter {
+            stream: Box::pin(stream),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    pub fn get_simple_stats(&self) -> Result<NetworkSimpleStatsResponse> {
+        self.block_on(self.client.get_simple_stats())
+    }
+
+    pub fn get_server_by_name<S: Into<String> + std::fmt::Display>(
+        &self,
+        server: S,
+    ) -> Result<ServerByNameResponse> {
+        self.block_on(self.client.get_server_by_name(server))
+    }
+
+    pub fn get_server_by_id<S: Into<String> + std::fmt::Display>(
+        &self,
+        id: S,
+    ) -> Result<ServerByNameResponse> {
+        self.block_on(self.client.get_server_by_id(id))
+    }
+
+    pub fn get_server(&self, server: &ServerRef) -> Result<ServerByNameResponse> {
+        self.block_on(self.client.get_server(server))
+    }
+
+    /// Resolves every server with at most `concurrency` calls in flight, in the order given.
+    pub fn get_servers<I: IntoIterator<Item = ServerRef>>(
+        &self,
+        servers: I,
+        concurrency: usize,
+    ) -> Vec<(ServerRef, Result<ServerByNameResponse>)> {
+        self.block_on(self.client.get_servers(servers, concurrency))
+    }
+
+    pub fn get_server_list_page(&self, offset: usize, limit: usize) -> Result<ServerListResponse> {
+        self.block_on(self.client.get_server_list_page(offset, limit))
+    }
+
+    /// Every online server matching `filter`, fetching pages as the iterator is advanced.
+    pub fn list_servers(&self, filter: ServerListFilter) -> Iter<Result<ServerListing>> {
+        self.iter(self.client.list_servers(filter))
+    }
+
+    /// Online servers whose name matches `query` by prefix, substring or a few typos, best first.
+    pub fn search_servers(&self, query: &str, limit: usize) -> Result<Vec<SearchMatch>> {
+        self.block_on(self.client.search_servers(query, limit))
+    }
+
+    /// Whether no server, online or not, is called `name`, ignoring case.
+    pub fn is_name_available(&self, name: &str) -> Result<bool> {
+        self.block_on(self.client.is_name_available(name))
+    }
+
+    pub fn get_plans(&self) -> Result<Vec<Plan>> {
+        self.block_on(self.client.get_plans())
+    }
+
+    pub fn get_categories(&self) -> Result<Vec<Category>> {
+        self.block_on(self.client.get_categories())
+    }
+
+    pub fn get_icons(&self) -> Result<Vec<Icon>> {
+        self.block_on(self.client.get_icons())
+    }
+
+    pub fn get_catalog(&self) -> Result<Catalog> {
+        self.block_on(self.client.get_catalog())
+    }
+
+    pub fn get_user_profile<S: Into<String> + std::fmt::Display>(
+        &self,
+        id: S,
+    ) -> Result<UserProfile> {
+        self.block_on(self.client.get_user_profile(id))
+    }
+
+    pub fn get_server_owner(&self, server: &ServerByNameResponse) -> Result<UserProfile> {
+        self.block_on(self.client.get_server_owner(server))
+    }
+
+    pub fn get_user<S: Into<String> + std::fmt::Display>(&self, id: S) -> Result<UserProfile> {
+        self.block_on(self.client.get_user(id))
+    }
+
+    pub fn get_current_user(&self) -> Result<UserProfile> {
+        self.block_on(self.client.get_current_user())
+    }
+
+    pub fn get_owned_servers<S: Into<String> + std::fmt::Display>(
+        &self,
+        id: S,
+    ) -> Result<Vec<ServerByNameResponse>> {
+        self.block_on(self.client.get_owned_servers(id))
+    }
+
+    /// A handle for managing the server with `_id` `id`, which the client's session must own.
+    pub fn owned_server<S: Into<String>>(&self, id: S) -> OwnedServer {
+        OwnedServer {
+            server: self.client.owned_server(id),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    pub fn watch_simple_stats(
+        &self,
+        interval: Duration,
+    ) -> Iter<Result<WatchEvent<NetworkSimpleStatsResponse>>> {
+        self.iter(self.client.watch_simple_stats(interval))
+    }
+
+    pub fn watch_server<S: Into<String>>(
+        &self,
+        name: S,
+        interval: Duration,
+    ) -> Iter<Result<WatchEvent<ServerByNameResponse>>> {
+        self.iter(self.client.watch_server(name, interval))
+    }
+}
+
+/// The runtime shared by a client's clones.
+///
+/// A plain [`Runtime`] panics when its last owner drops it inside an async context, this shuts it
+/// down in the background instead.
+#[derive(Debug)]
+struct ClientRuntime(Option<Runtime>);
+
+impl Deref for ClientRuntime {
+    type Target = Runtime;
+
+    fn deref(&self) -> &Runtime {
+        self.0.as_ref().expect("The runtime is only taken on drop.")
+    }
+}
+
+impl Drop for ClientRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// A blocking [`rest::Call`], see [`MinehutClient::call`].
+pub struct Call {
+    call: rest::Call,
+    runtime: Arc<ClientRuntime>,
+}
+
+impl Call {
+    /// Names the endpoint this call belongs to, ex: `/server/{name}`.
+    pub fn endpoint<S: Into<String>>(mut self, endpoint: S) -> Self {
+        self.call = self.call.endpoint(endpoint);
+        self
+    }
+
+    /// Overrides the client's retry policy for this call.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.call = self.call.retry(policy);
+        self
+    }
+
+    pub fn query<Q: Serialize + ?Sized>(mut self, query: &Q) -> Self {
+        self.call = self.call.query(query);
+        self
+    }
+
+    /// Sends `body` as JSON with the request.
+    pub fn json<B: Serialize + ?Sized>(mut self, body: &B) -> Self {
+        self.call = self.call.json(body);
+        self
+    }
+
+    pub fn path(&self) -> &str {
+        self.call.path()
+    }
+
+    pub fn get<T: DeserializeOwned>(&self) -> Result<T> {
+        self.runtime.block_on(self.call.get())
+    }
+
+    pub fn post<T: DeserializeOwned>(&self) -> Result<T> {
+        self.runtime.block_on(self.call.post())
+    }
+
+    pub fn put<T: DeserializeOwned>(&self) -> Result<T> {
+        self.runtime.block_on(self.call.put())
+    }
+
+    pub fn patch<T: DeserializeOwned>(&self) -> Result<T> {
+        self.runtime.block_on(self.call.patch())
+    }
+
+    pub fn delete<T: DeserializeOwned>(&self) -> Result<T> {
+        self.runtime.block_on(self.call.delete())
+    }
+
+    /// Sends the call with `method`, keeping the response status and headers.
+    pub fn send<T: DeserializeOwned>(&self, method: Method) -> Result<ApiResponse<T>> {
+        self.runtime.block_on(self.call.send(method))
+    }
+}
+
+/// A blocking [`manage::OwnedServer`], see [`MinehutClient::owned_server`].
+#[derive(Clone, Debug)]
+pub struct OwnedServer {
+    server: manage::OwnedServer,
+    runtime: Arc<ClientRuntime>,
+}
+
+impl OwnedServer {
+    pub fn id(&self) -> &str {
+        self.server.id()
+    }
+
+    pub fn info(&self) -> Result<ServerByNameResponse> {
+        self.runtime.block_on(self.server.info())
+    }
+
+    pub fn status(&self) -> Result<ServerStatus> {
+        self.runtime.block_on(self.server.status())
+    }
+
+    pub fn data(&self) -> Result<ServerData> {
+        self.runtime.block_on(self.server.data())
+    }
+
+    pub fn properties(&self) -> Result<ServerProperties> {
+        self.runtime.block_on(self.server.properties())
+    }
+
+    pub fn start(&self) -> Result<()> {
+        self.runtime.block_on(self.server.start())
+    }
+
+    pub fn stop(&self) -> Result<()> {
+        self.runtime.block_on(self.server.stop())
+    }
+
+    pub fn restart(&self) -> Result<()> {
+        self.runtime.block_on(self.server.restart())
+    }
+
+    pub fn hibernate(&self) -> Result<()> {
+        self.runtime.block_on(self.server.hibernate())
+    }
+
+    pub fn set_property<V: Serialize>(&self, field: &str, value: V) -> Result<()> {
+        self.runtime
+            .block_on(self.server.set_property(field, value))
+    }
+
+    pub fn set_motd(&self, motd: &str) -> Result<()> {
+        self.runtime.block_on(self.server.set_motd(motd))
+    }
+
+    pub fn set_visibility(&self, visibility: bool) -> Result<()> {
+        self.runtime
+            .block_on(self.server.set_visibility(visibility))
+    }
+
+    /// Runs `change` against this server, fetching it before and after, ex:
+    /// `server.change(|server| server.set_motd("Back soon!"))`.
+    pub fn change<F: FnOnce(&OwnedServer) -> Result<()>>(&self, change: F) -> Result<ServerChange> {
+        let before = self.info()?;
+        change(self)?;
+        let after = self.info()?;
+        Ok(ServerChange { before, after })
+    }
+}
+
+/// A stream polled one item at a time, see [`MinehutClient::iter`].
+#[must_use = "iterators do nothing unless advanced"]
+pub struct Iter<T> {
+    stream: Pin<Box<dyn Stream<Item = T> + Send>>,
+    runtime: Arc<ClientRuntime>,
+}
+
+impl<T> Iterator for Iter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+static DEFAULT_CLIENT: OnceLock<MinehutClient> = OnceLock::new();
+
+/// Wraps [`client::default_client`], used by the free endpoint functions.
+pub fn default_client() -> &'static MinehutClient {
+    DEFAULT_CLIENT.get_or_init(|| MinehutClient::new(client::default_client().clone()))
+}
+
+pub fn get_simple_stats() -> Result<NetworkSimpleStatsResponse> {
+    default_client().get_simple_stats()
+}
+
+pub fn get_server_by_name<S: Into<String> + std::fmt::Display>(
+    server: S,
+) -> Result<ServerByNameResponse> {
+    default_client().get_server_by_name(server)
+}
+
+pub fn get_server_by_id<S: Into<String> + std::fmt::Display>(
+    id: S,
+) -> Result<ServerByNameResponse> {
+    default_client().get_server_by_id(id)
+}
+
+pub fn get_servers<I: IntoIterator<Item = ServerRef>>(
+    servers: I,
+    concurrency: usize,
+) -> Vec<(ServerRef, Result<ServerByNameResponse>)> {
+    default_client().get_servers(servers, concurrency)
+}
+
+pub fn list_servers(filter: ServerListFilter) -> Iter<Result<ServerListing>> {
+    default_client().list_servers(filter)
+}
+
+pub fn search_servers(query: &str, limit: usize) -> Result<Vec<SearchMatch>> {
+    default_client().search_servers(query, limit)
+}
+
+pub fn is_name_available(name: &str) -> Result<bool> {
+    default_client().is_name_available(name)
+}
+
+pub fn get_plans() -> Result<Vec<Plan>> {
+    default_client().get_plans()
+}
+
+pub fn get_categories() -> Result<Vec<Category>> {
+    default_client().get_categories()
+}
+
+pub fn get_icons() -> Result<Vec<Icon>> {
+    default_client().get_icons()
+}
+
+pub fn get_catalog() -> Result<Catalog> {
+    default_client().get_catalog()
+}
+
+pub fn get_user_profile<S: Into<String> + std::fmt::Display>(id: S) -> Result<UserProfile> {
+    default_client().get_user_profile(id)
+}
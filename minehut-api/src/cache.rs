@@ -0,0 +1,308 @@
+use crate::rest::RawResponse;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long successful GET responses are reused before Minehut's API is asked again.
+#[derive(Clone, Debug)]
+pub struct CacheConfig {
+    default_ttl: Duration,
+    ttls: HashMap<String, Duration>,
+    max_entries: usize,
+}
+
+impl CacheConfig {
+    pub fn new(default_ttl: Duration) -> Self {
+        Self {
+            default_ttl,
+            ttls: HashMap::new(),
+            max_entries: 1024,
+        }
+    }
+
+    /// Overrides the ttl for one endpoint, ex: `/server/{name}`. A zero ttl disables caching.
+    pub fn ttl<S: Into<String>>(mut self, endpoint: S, ttl: Duration) -> Self {
+        self.ttls.insert(endpoint.into(), ttl);
+        self
+    }
+
+    /// The most responses kept at once, the oldest are evicted first.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    fn ttl_for(&self, endpoint: &str) -> Duration {
+        self.ttls.get(endpoint).copied().unwrap_or(self.default_ttl)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Served from a fresh entry without waiting.
+    pub hits: u64,
+    /// Served from an entry another identical in-flight call just fetched.
+    pub coalesced: u64,
+    /// Served from a stale entry after Minehut answered 304 Not Modified.
+    pub revalidated: u64,
+    /// Fetched in full from Minehut's API.
+    pub misses: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    response: RawResponse,
+    fetched_at: Instant,
+    ttl: Duration,
+}
+
+impl Entry {
+    fn is_fresh(&self) -> bool {
+        self.fetched_at.elapsed() < self.ttl
+    }
+}
+
+/// The validators to send when revalidating a stale entry.
+#[derive(Debug, Default)]
+pub(crate) struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ResponseCache {
+    config: CacheConfig,
+    entries: Mutex<HashMap<String, Entry>>,
+    in_flight: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    hits: AtomicU64,
+    coalesced: AtomicU64,
+    revalidated: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ResponseCache {
+    pub fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            coalesced: AtomicU64::new(0),
+            revalidated: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            revalidated: self.revalidated.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Drops every entry under the same top level resource as `path`, ex: a write to
+    /// `/server/{id}/change_motd` drops cached servers by name or id and the server list, since
+    /// those are keyed by names and queries the write doesn't know about.
+    pub(crate) fn invalidate_resource(&self, path: &str) {
+        let Some(resource) = resource_of(path) else {
+            return;
+        };
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|cached, _| resource_of(cached) != Some(resource));
+    }
+
+    pub(crate) fn is_enabled_for(&self, endpoint: &str) -> bool {
+        !self.config.ttl_for(endpoint).is_zero()
+    }
+
+    pub(crate) fn fresh(&self, path: &str) -> Option<RawResponse> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(path)
+            .filter(|entry| entry.is_fresh())
+            .map(|entry| entry.response.clone())
+    }
+
+    pub(crate) fn hit(&self, path: &str, coalesced: bool) -> Option<RawResponse> {
+        let response = self.fresh(path)?;
+        if coalesced {
+            self.coalesced.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        Some(response)
+    }
+
+    /// Waits for any identical call already in flight, the guard must be held until the response
+    /// is stored.
+    pub(crate) async fn lock(&self, path: &str) -> InFlightGuard<'_> {
+        let lock = Arc::clone(
+            self.in_flight
+                .lock()
+                .unwrap()
+                .entry(path.to_string())
+                .or_default(),
+        );
+        let guard = Arc::clone(&lock).lock_owned().await;
+        InFlightGuard {
+            cache: self,
+            path: path.to_string(),
+            lock,
+            _guard: guard,
+        }
+    }
+
+    pub(crate) fn validators(&self, path: &str) -> Validators {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(path)
+            .map(|entry| Validators {
+                etag: entry.response.header("etag").map(str::to_string),
+                last_modified: entry.response.header("last-modified").map(str::to_string),
+            })
+            .unwrap_or_default()
+    }
+
+    /// Marks the stale entry for `path` fresh again after a 304.
+    pub(crate) fn revalidate(&self, path: &str) -> Option<RawResponse> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get_mut(path)?;
+        entry.fetched_at = Instant::now();
+        self.revalidated.fetch_add(1, Ordering::Relaxed);
+        Some(entry.response.clone())
+    }
+
+    pub(crate) fn store(&self, endpoint: &str, path: &str, response: &RawResponse) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        if !(200..300).contains(&response.status) {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.config.max_entries && !entries.contains_key(path) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.fetched_at)
+                .map(|(path, _)| path.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            path.to_string(),
+            Entry {
+                response: response.clone(),
+                fetched_at: Instant::now(),
+                ttl: self.config.ttl_for(endpoint),
+            },
+        );
+    }
+}
+
+/// The whole first segment of `path`, ex: `server` for `/server/MockHub?byName=true`, or `None`
+/// for the root.
+fn resource_of(path: &str) -> Option<&str> {
+    let path = path.split('?').next().unwrap_or_default();
+    let resource = path.trim_start_matches('/').split('/').next()?;
+    match resource {
+        "" => None,
+        // `/servers` lists the same servers `/server/{id}` returns
+        "servers" => Some("server"),
+        resource => Some(resource),
+    }
+}
+
+pub(crate) struct InFlightGuard<'cache> {
+    cache: &'cache ResponseCache,
+    path: String,
+    lock: Arc<tokio::sync::Mutex<()>>,
+    _guard: tokio::sync::OwnedMutexGuard<()>,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.cache.in_flight.lock().unwrap();
+        // only the map and this guard still own the lock, so nobody else is waiting on it
+        if Arc::strong_count(&self.lock) <= 3 {
+            in_flight.remove(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn cache(paths: &[&str]) -> ResponseCache {
+        let cache = ResponseCache::new(CacheConfig::new(Duration::from_secs(60)));
+        let response = RawResponse {
+            status: 200,
+            headers: BTreeMap::new(),
+            body: "{}".to_string(),
+        };
+        for path in paths {
+            cache.store(path, path, &response);
+        }
+        cache
+    }
+
+    fn cached(cache: &ResponseCache) -> Vec<String> {
+        let mut paths = cache
+            .entries
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn writes_drop_the_whole_resource() {
+        let cache = cache(&[
+            "/server/MockHub?byName=true",
+            "/server/5f1a2b3c4d5e6f7a8b9c0d1f",
+            "/servers?limit=100",
+            "/user/6a7b8c9d0e1f2a3b4c5d6e7f",
+        ]);
+        cache.invalidate_resource("/server/5f1a2b3c4d5e6f7a8b9c0d1f/change_motd");
+        assert_eq!(cached(&cache), ["/user/6a7b8c9d0e1f2a3b4c5d6e7f"]);
+    }
+
+    #[test]
+    fn resources_only_match_whole_segments() {
+        let cache = cache(&[
+            "/user/6a7b8c9d0e1f2a3b4c5d6e7f",
+            "/users/6a7b8c9d0e1f2a3b4c5d6e7f/profile",
+            "/network/simple_stats",
+        ]);
+        cache.invalidate_resource("/users/login");
+        assert_eq!(
+            cached(&cache),
+            ["/network/simple_stats", "/user/6a7b8c9d0e1f2a3b4c5d6e7f"]
+        );
+        cache.invalidate_resource("/net");
+        assert_eq!(cached(&cache).len(), 2);
+    }
+
+    #[test]
+    fn the_root_invalidates_nothing() {
+        let cache = cache(&["/network/simple_stats", "/plans"]);
+        cache.invalidate_resource("/");
+        cache.invalidate_resource("");
+        assert_eq!(cached(&cache).len(), 2);
+    }
+}
@@ -0,0 +1,166 @@
+//! Records calls to Minehut's API and replays them without network access.
+//!
+//! A cassette file is JSON Lines, one [`Interaction`] per line in the order the calls were made.
+//! When replaying, each request is answered by the next unused interaction with the same method
+//! and path; once those run out the last one keeps being served, so polling loops can replay a
+//! short recording forever.
+//!
+//! Secrets never reach the file: headers and JSON fields which look like tokens, ex: the `token`
+//! and `session_id` of a login, are recorded as `<redacted>`, as are secret query parameters.
+
+use crate::auth::{is_secret_name, redact};
+use crate::rest::RawResponse;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+const REDACTED: &str = "<redacted>";
+
+#[derive(thiserror::Error, Debug)]
+pub enum CassetteError {
+    #[error("Failed to read or write the cassette file.")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to decode cassette interaction on line {line}.")]
+    Decode {
+        line: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct Interaction {
+    pub method: String,
+    pub path: String,
+    #[serde(flatten)]
+    pub response: RawResponse,
+}
+
+#[derive(Debug)]
+enum Tape {
+    Recording(BufWriter<File>),
+    Replaying(HashMap<(String, String), VecDeque<RawResponse>>),
+}
+
+#[derive(Debug)]
+pub struct Cassette {
+    tape: Mutex<Tape>,
+}
+
+impl Cassette {
+    /// Records every call into a new cassette at `path`, replacing anything already there.
+    pub fn record<P: AsRef<Path>>(path: P) -> Result<Self, CassetteError> {
+        Ok(Self {
+            tape: Mutex::new(Tape::Recording(BufWriter::new(File::create(path)?))),
+        })
+    }
+
+    /// Serves every call from the cassette at `path`.
+    pub fn replay<P: AsRef<Path>>(path: P) -> Result<Self, CassetteError> {
+        Self::replay_from(BufReader::new(File::open(path)?))
+    }
+
+    pub fn replay_from<R: BufRead>(reader: R) -> Result<Self, CassetteError> {
+        let mut interactions = HashMap::<(String, String), VecDeque<RawResponse>>::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let interaction = serde_json::from_str::<Interaction>(&line).map_err(|source| {
+                CassetteError::Decode {
+                    line: index + 1,
+                    source,
+                }
+            })?;
+            interactions
+                .entry((interaction.method, interaction.path))
+                .or_default()
+                .push_back(interaction.response);
+        }
+
+        Ok(Self {
+            tape: Mutex::new(Tape::Replaying(interactions)),
+        })
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        matches!(*self.tape.lock().unwrap(), Tape::Replaying(_))
+    }
+
+    pub(crate) fn replay_next(&self, method: &str, path: &str) -> Option<RawResponse> {
+        let mut tape = self.tape.lock().unwrap();
+        let Tape::Replaying(interactions) = &mut *tape else {
+            return None;
+        };
+
+        let responses = interactions.get_mut(&(method.to_string(), redact(path).into_owned()))?;
+        if responses.len() > 1 {
+            responses.pop_front()
+        } else {
+            responses.front().cloned()
+        }
+    }
+
+    pub(crate) fn record_next(&self, method: &str, path: &str, response: &RawResponse) {
+        let mut tape = self.tape.lock().unwrap();
+        let Tape::Recording(writer) = &mut *tape else {
+            return;
+        };
+
+        let interaction = Interaction {
+            method: method.to_string(),
+            path: redact(path).into_owned(),
+            response: scrub(response),
+        };
+        let written = serde_json::to_writer(&mut *writer, &interaction)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writer.write_all(b"\n"))
+            .and_then(|_| writer.flush());
+        if let Err(err) = written {
+            log::error!(target: "MinehutAPI/Cassette", "Failed to record {method} {path}: {err:?}");
+        }
+    }
+}
+
+/// A copy of `response` which is safe to write to disk.
+fn scrub(response: &RawResponse) -> RawResponse {
+    let mut response = response.clone();
+    for (name, value) in &mut response.headers {
+        if name == "set-cookie" || is_secret_name(name) {
+            *value = REDACTED.to_string();
+        }
+    }
+    // bodies without secrets are kept byte for byte
+    if let Ok(mut body) = serde_json::from_str::<Value>(&response.body) {
+        if scrub_value(&mut body) {
+            response.body = body.to_string();
+        }
+    }
+    response
+}
+
+/// Masks every secret looking string field, returning whether any were found.
+fn scrub_value(value: &mut Value) -> bool {
+    match value {
+        Value::Object(fields) => {
+            let mut scrubbed = false;
+            for (name, value) in fields {
+                if value.is_string() && is_secret_name(name) {
+                    *value = Value::from(REDACTED);
+                    scrubbed = true;
+                } else {
+                    scrubbed |= scrub_value(value);
+                }
+            }
+            scrubbed
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .fold(false, |scrubbed, item| scrub_value(item) | scrubbed),
+        _ => false,
+    }
+}
@@ -0,0 +1,169 @@
+use crate::client::{default_client, MinehutClient};
+use crate::error::Result;
+use crate::server::ServerPlan;
+use std::collections::HashMap;
+
+/// A plan a server can run on, what [`ServerByNameResponse::server_plan`] names.
+///
+/// [`ServerByNameResponse::server_plan`]: crate::rest::ServerByNameResponse::server_plan
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct Plan {
+    #[serde(rename = "_id")]
+    pub id: Option<String>,
+    /// The plan as servers reference it, ex: `MH20`.
+    pub name: String,
+    pub display_name: Option<String>,
+    /// Memory in megabytes.
+    pub ram: Option<f64>,
+    /// Monthly price in US dollars, `None` for plans only bought with credits.
+    pub price: Option<f64>,
+    pub credits_per_day: Option<f64>,
+    pub max_players: Option<f64>,
+    pub backup_slots: Option<f64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Plan {
+    pub fn server_plan(&self) -> ServerPlan {
+        ServerPlan::from(self.name.as_str())
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// A category servers can list themselves under, what `categories` on a server holds.
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct Category {
+    /// The category as servers reference it, ex: `survival`.
+    pub name: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Category {
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// An icon from the store, what `purchased_icons` on a server holds the ids of.
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct Icon {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub icon_name: Option<String>,
+    pub display_name: Option<String>,
+    /// Price in credits.
+    pub price: Option<f64>,
+    /// The rank needed to buy the icon, if any.
+    pub rank: Option<String>,
+    pub available: Option<bool>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Icon {
+    pub fn display_name(&self) -> &str {
+        self.display_name
+            .as_deref()
+            .or(self.icon_name.as_deref())
+            .unwrap_or(&self.id)
+    }
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+struct WrappedPlans {
+    plans: Vec<Plan>,
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+struct WrappedCategories {
+    categories: Vec<Category>,
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+struct WrappedIcons {
+    icons: Vec<Icon>,
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+/// Every plan, category and icon, for turning the ids on servers into display names and prices.
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug, Default)]
+pub struct Catalog {
+    pub plans: Vec<Plan>,
+    pub categories: Vec<Category>,
+    pub icons: Vec<Icon>,
+}
+
+impl Catalog {
+    pub fn plan(&self, plan: &ServerPlan) -> Option<&Plan> {
+        self.plans
+            .iter()
+            .find(|candidate| candidate.server_plan() == *plan)
+    }
+
+    pub fn category(&self, name: &str) -> Option<&Category> {
+        self.categories
+            .iter()
+            .find(|category| category.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn icon(&self, id: &str) -> Option<&Icon> {
+        self.icons.iter().find(|icon| icon.id == id)
+    }
+}
+
+impl MinehutClient {
+    pub async fn get_plans(&self) -> Result<Vec<Plan>> {
+        Ok(self.call("/plans").get::<WrappedPlans>().await?.plans)
+    }
+
+    pub async fn get_categories(&self) -> Result<Vec<Category>> {
+        Ok(self
+            .call("/categories")
+            .get::<WrappedCategories>()
+            .await?
+            .categories)
+    }
+
+    pub async fn get_icons(&self) -> Result<Vec<Icon>> {
+        Ok(self.call("/icons").get::<WrappedIcons>().await?.icons)
+    }
+
+    /// Fetches the plans, categories and icons at once.
+    pub async fn get_catalog(&self) -> Result<Catalog> {
+        let (plans, categories, icons) =
+            futures::try_join!(self.get_plans(), self.get_categories(), self.get_icons())?;
+        Ok(Catalog {
+            plans,
+            categories,
+            icons,
+        })
+    }
+}
+
+pub async fn get_plans() -> Result<Vec<Plan>> {
+    default_client().get_plans().await
+}
+
+pub async fn get_categories() -> Result<Vec<Category>> {
+    default_client().get_categories().await
+}
+
+pub async fn get_icons() -> Result<Vec<Icon>> {
+    default_client().get_icons().await
+}
+
+pub async fn get_catalog() -> Result<Catalog> {
+    default_client().get_catalog().await
+}
@@ -0,0 +1,291 @@
+use crate::auth::Session;
+use crate::cache::{CacheConfig, ResponseCache};
+use crate::cassette::Cassette;
+use crate::drift::{DriftDetector, DriftMode};
+use crate::environment::Environment;
+use crate::error::{MinehutError, Result};
+use crate::metrics::MetricsObserver;
+use crate::rest::Call;
+use crate::retry::{RateLimiter, RetryPolicy};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+const DEFAULT_USER_AGENT: &str = concat!("MHTools/", env!("CARGO_PKG_VERSION"));
+
+/// A reusable handle to Minehut's API.
+///
+/// Cloning is cheap; every clone shares the same connection pool.
+#[derive(Clone, Debug)]
+pub struct MinehutClient {
+    http: reqwest::Client,
+    environment: Environment,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    drift_detector: Option<Arc<DriftDetector>>,
+    cassette: Option<Arc<Cassette>>,
+    cache: Option<Arc<ResponseCache>>,
+    session: Option<Arc<Session>>,
+    metrics: Option<Arc<dyn MetricsObserver>>,
+}
+
+impl MinehutClient {
+    pub fn builder() -> MinehutClientBuilder {
+        MinehutClientBuilder::default()
+    }
+
+    /// Builds a client against the environment named by `MINEHUT_URL`, falling back to the dev api.
+    pub fn from_env() -> Result<Self> {
+        MinehutClientBuilder::from_env().build()
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn api(&self) -> &str {
+        self.environment.host()
+    }
+
+    pub fn scheme(&self) -> &str {
+        self.environment.scheme()
+    }
+
+    /// The full url a call to `path` will hit.
+    pub fn url_for<S: AsRef<str>>(&self, path: S) -> String {
+        self.environment.url_for(path)
+    }
+
+    pub fn call<S: Into<String>>(&self, path: S) -> Call {
+        Call::with_client(self.clone(), path)
+    }
+
+    /// The retry policy used by calls which don't set their own.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_deref()
+    }
+
+    pub fn drift_detector(&self) -> Option<&DriftDetector> {
+        self.drift_detector.as_deref()
+    }
+
+    pub fn cassette(&self) -> Option<&Cassette> {
+        self.cassette.as_deref()
+    }
+
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.cache.as_deref()
+    }
+
+    pub fn session(&self) -> Option<&Session> {
+        self.session.as_deref()
+    }
+
+    pub fn metrics(&self) -> Option<&dyn MetricsObserver> {
+        self.metrics.as_deref()
+    }
+
+    /// A clone of this client which calls as `session`, sharing everything else.
+    pub fn with_session(&self, session: Session) -> Self {
+        Self {
+            session: Some(Arc::new(session)),
+            ..self.clone()
+        }
+    }
+
+    /// A clone of this client which calls without any session.
+    pub fn anonymous(&self) -> Self {
+        Self {
+            session: None,
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn http(&self) -> &reqwest::Client {
+        &self.http
+    }
+}
+
+static DEFAULT_CLIENT: OnceLock<MinehutClient> = OnceLock::new();
+
+/// The client used by [`Call::new`] and the free endpoint functions.
+pub fn default_client() -> &'static MinehutClient {
+    DEFAULT_CLIENT.get_or_init(|| {
+        MinehutClient::from_env().expect("Failed to build the default Minehut client.")
+    })
+}
+
+/// Replaces the default client, this must happen before the default client is first used.
+///
+/// Hands the client back if the default client was already initialized.
+#[allow(clippy::result_large_err)]
+pub fn init_default_client(client: MinehutClient) -> std::result::Result<(), MinehutClient> {
+    DEFAULT_CLIENT.set(client)
+}
+
+pub struct MinehutClientBuilder {
+    environment: Environment,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: String,
+    default_headers: HeaderMap,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    drift_mode: Option<DriftMode>,
+    cassette: Option<Arc<Cassette>>,
+    cache: Option<CacheConfig>,
+    session: Option<Session>,
+    metrics: Option<Arc<dyn MetricsObserver>>,
+}
+
+impl Default for MinehutClientBuilder {
+    fn default() -> Self {
+        Self {
+            environment: Environment::default(),
+            timeout: None,
+            connect_timeout: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            default_headers: HeaderMap::new(),
+            retry_policy: RetryPolicy::none(),
+            rate_limiter: None,
+            drift_mode: None,
+            cassette: None,
+            cache: None,
+            session: None,
+            metrics: None,
+        }
+    }
+}
+
+impl MinehutClientBuilder {
+    /// A builder against the environment named by `MINEHUT_URL`, see [`Environment::from_env`].
+    pub fn from_env() -> Self {
+        Self::default().environment(Environment::from_env())
+    }
+
+    /// Sets which deployment of Minehut's API to call, the dev api by default.
+    pub fn environment(mut self, environment: Environment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    /// Sets the api host, ex: `api.minehut.com`, see [`Environment::custom`].
+    ///
+    /// A leading scheme (`http://localhost:8080`) overrides the configured scheme.
+    pub fn api<S: AsRef<str>>(mut self, api: S) -> Self {
+        let api = api.as_ref();
+        self.environment = if api.contains("://") {
+            Environment::custom(api)
+        } else {
+            Environment::custom(format!("{}://{api}", self.environment.scheme()))
+        };
+        self
+    }
+
+    pub fn scheme<S: AsRef<str>>(mut self, scheme: S) -> Self {
+        self.environment = self.environment.with_scheme(scheme);
+        self
+    }
+
+    /// Total time allowed for a single request, from connecting to reading the body.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers.extend(headers);
+        self
+    }
+
+    /// Retries failed calls with `policy` unless a call overrides it, off by default.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Caps every call made through the client to `per_second` requests on average.
+    pub fn rate_limit(mut self, per_second: f64, burst: u32) -> Self {
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(per_second, burst)));
+        self
+    }
+
+    /// Compares every response against the last one from the same endpoint and reports changes.
+    pub fn drift_mode(mut self, mode: DriftMode) -> Self {
+        self.drift_mode = Some(mode);
+        self
+    }
+
+    /// Records calls into, or replays them from, `cassette`.
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(Arc::new(cassette));
+        self
+    }
+
+    /// Reuses successful GET responses for as long as `config` allows, off by default.
+    pub fn cache(mut self, config: CacheConfig) -> Self {
+        self.cache = Some(config);
+        self
+    }
+
+    /// Sends `session`'s token with every call, logging in when needed.
+    pub fn session(mut self, session: Session) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Reports every call to `observer`, ex: an [`ApiMetrics`] shared with whatever reads it.
+    ///
+    /// [`ApiMetrics`]: crate::metrics::ApiMetrics
+    pub fn metrics(mut self, observer: Arc<dyn MetricsObserver>) -> Self {
+        self.metrics = Some(observer);
+        self
+    }
+
+    pub fn build(self) -> Result<MinehutClient> {
+        let mut http = reqwest::Client::builder()
+            .user_agent(self.user_agent)
+            .default_headers(self.default_headers);
+        if let Some(timeout) = self.timeout {
+            http = http.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            http = http.connect_timeout(timeout);
+        }
+
+        Ok(MinehutClient {
+            http: http.build().map_err(MinehutError::Build)?,
+            environment: self.environment,
+            retry_policy: self.retry_policy,
+            rate_limiter: self.rate_limiter,
+            drift_detector: self
+                .drift_mode
+                .map(|mode| Arc::new(DriftDetector::new(mode))),
+            cassette: self.cassette,
+            cache: self
+                .cache
+                .map(|config| Arc::new(ResponseCache::new(config))),
+            session: self.session.map(Arc::new),
+            metrics: self.metrics,
+        })
+    }
+}
@@ -0,0 +1,508 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+/// The JSON type of a field as it was last seen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldKind {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl FieldKind {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => FieldKind::Null,
+            Value::Bool(_) => FieldKind::Bool,
+            Value::Number(_) => FieldKind::Number,
+            Value::String(_) => FieldKind::String,
+            Value::Array(_) => FieldKind::Array,
+            Value::Object(_) => FieldKind::Object,
+        }
+    }
+}
+
+impl std::fmt::Display for FieldKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FieldKind::Null => "null",
+            FieldKind::Bool => "bool",
+            FieldKind::Number => "number",
+            FieldKind::String => "string",
+            FieldKind::Array => "array",
+            FieldKind::Object => "object",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldDrift {
+    Added {
+        field: String,
+        kind: FieldKind,
+    },
+    Missing {
+        field: String,
+        kind: FieldKind,
+    },
+    Retyped {
+        field: String,
+        was: FieldKind,
+        now: FieldKind,
+    },
+}
+
+impl std::fmt::Display for FieldDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldDrift::Added { field, kind } => write!(f, "+ {field} ({kind})"),
+            FieldDrift::Missing { field, kind } => write!(f, "- {field} ({kind})"),
+            FieldDrift::Retyped { field, was, now } => write!(f, "~ {field} ({was} -> {now})"),
+        }
+    }
+}
+
+/// Every change seen in one response compared to what its endpoint has sent before.
+#[derive(Clone, Debug)]
+pub struct DriftReport {
+    pub endpoint: String,
+    pub changes: Vec<FieldDrift>,
+}
+
+pub type DriftCallback = Arc<dyn Fn(&DriftReport) + Send + Sync>;
+
+#[derive(Clone)]
+pub enum DriftMode {
+    /// Logs every report as a warning.
+    Log,
+    Callback(DriftCallback),
+}
+
+impl std::fmt::Debug for DriftMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DriftMode::Log => f.write_str("Log"),
+            DriftMode::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Shape {
+    fields: BTreeMap<String, FieldKind>,
+    /// Arrays which were empty, nothing inside them can be called added or missing.
+    empty_arrays: Vec<String>,
+}
+
+impl Shape {
+    fn is_unobserved(&self, field: &str) -> bool {
+        self.empty_arrays
+            .iter()
+            .any(|array| field.starts_with(array.as_str()))
+    }
+}
+
+/// How many responses in a row a known field has to be absent from before it is reported missing,
+/// so optional fields which only some servers send aren't.
+const MISSING_AFTER: u32 = 5;
+
+/// Everything an endpoint has sent so far.
+#[derive(Debug, Default)]
+struct Baseline {
+    /// Whether any response has been observed, unknown fields can be reported before that.
+    observed: bool,
+    /// Every field ever seen, with the last concrete kind it had.
+    fields: BTreeMap<String, FieldKind>,
+    /// How many responses in a row each known field has been absent from.
+    absent: HashMap<String, u32>,
+    /// Fields already reported as added, from the shape or from a model's `extra`.
+    announced: HashSet<String>,
+}
+
+impl Baseline {
+    /// Folds `shape` into the baseline, returning what is new or changed about it.
+    fn update(&mut self, shape: &Shape) -> Vec<FieldDrift> {
+        let mut changes = Vec::new();
+        // the first items of an array which was always empty so far are not new fields
+        let unseen_arrays = shape
+            .fields
+            .keys()
+            .filter(|field| !self.fields.contains_key(*field))
+            .flat_map(|field| enclosing_arrays(field))
+            .filter(|array| !self.fields.keys().any(|known| known.starts_with(array)))
+            .collect::<HashSet<_>>();
+
+        for (field, kind) in &shape.fields {
+            self.absent.remove(field);
+            match self.fields.get_mut(field) {
+                None => {
+                    self.fields.insert(field.clone(), *kind);
+                    if enclosing_arrays(field).any(|array| unseen_arrays.contains(array)) {
+                        continue;
+                    }
+                    self.announced.insert(field.clone());
+                    changes.push(FieldDrift::Added {
+                        field: field.clone(),
+                        kind: *kind,
+                    });
+                }
+                Some(was) if *was == FieldKind::Null => *was = *kind,
+                Some(was) if *kind != FieldKind::Null && was != kind => {
+                    changes.push(FieldDrift::Retyped {
+                        field: field.clone(),
+                        was: *was,
+                        now: *kind,
+                    });
+                    *was = *kind;
+                }
+                Some(_) => {}
+            }
+        }
+
+        for (field, kind) in &self.fields {
+            if shape.fields.contains_key(field) || shape.is_unobserved(field) {
+                continue;
+            }
+            let absent = self.absent.entry(field.clone()).or_default();
+            *absent += 1;
+            if *absent == MISSING_AFTER {
+                changes.push(FieldDrift::Missing {
+                    field: field.clone(),
+                    kind: *kind,
+                });
+            }
+        }
+        changes
+    }
+}
+
+/// Every array `field` is inside of, outermost first, ex: `servers[]` for `servers[].name`.
+fn enclosing_arrays(field: &str) -> impl Iterator<Item = &str> {
+    field
+        .match_indices("[]")
+        .map(move |(index, _)| &field[..index + 2])
+}
+
+/// Compares the shape of each response against everything its endpoint has sent before.
+///
+/// The first response from an endpoint becomes its baseline. After that, fields never seen
+/// before are reported as added, fields which change type as retyped, and fields absent from
+/// [`MISSING_AFTER`] responses in a row as missing.
+#[derive(Debug)]
+pub struct DriftDetector {
+    mode: DriftMode,
+    baselines: Mutex<HashMap<String, Baseline>>,
+}
+
+impl DriftDetector {
+    pub fn new(mode: DriftMode) -> Self {
+        Self {
+            mode,
+            baselines: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records the shape of `payload` and reports any drift from the endpoint's baseline.
+    pub fn observe(&self, endpoint: &str, payload: &Value) -> Option<DriftReport> {
+        let mut shape = Shape::default();
+        collect_shape(payload, "", &mut shape);
+
+        let changes = {
+            let mut baselines = self.baselines.lock().unwrap();
+            let baseline = baselines.entry(endpoint.to_string()).or_default();
+            if !baseline.observed {
+                baseline.observed = true;
+                baseline.fields = shape.fields;
+                return None;
+            }
+            baseline.update(&shape)
+        };
+        self.report(endpoint, changes)
+    }
+
+    /// Reports fields a model didn't know, ex: what its `extra` caught, the first time each is
+    /// seen. Unlike [`DriftDetector::observe`], this covers the response which became the
+    /// endpoint's baseline too.
+    pub fn observe_unknown<'a, I: IntoIterator<Item = (String, &'a Value)>>(
+        &self,
+        endpoint: &str,
+        fields: I,
+    ) -> Option<DriftReport> {
+        let changes = {
+            let mut baselines = self.baselines.lock().unwrap();
+            let baseline = baselines.entry(endpoint.to_string()).or_default();
+            fields
+                .into_iter()
+                .filter(|(field, _)| baseline.announced.insert(field.clone()))
+                .map(|(field, value)| FieldDrift::Added {
+                    field,
+                    kind: FieldKind::of(value),
+                })
+                .collect::<Vec<_>>()
+        };
+        self.report(endpoint, changes)
+    }
+
+    fn report(&self, endpoint: &str, changes: Vec<FieldDrift>) -> Option<DriftReport> {
+        if changes.is_empty() {
+            return None;
+        }
+
+        let report = DriftReport {
+            endpoint: endpoint.to_string(),
+            changes,
+        };
+        match &self.mode {
+            DriftMode::Log => {
+                for change in &report.changes {
+                    log::warn!(target: "MinehutAPI/Drift", "{} drifted: {change}", report.endpoint);
+                }
+            }
+            DriftMode::Callback(callback) => callback(&report),
+        }
+        Some(report)
+    }
+}
+
+fn collect_shape(value: &Value, path: &str, shape: &mut Shape) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let field = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                record(shape, &field, FieldKind::of(value));
+                collect_shape(value, &field, shape);
+            }
+        }
+        Value::Array(items) => {
+            let field = format!("{path}[]");
+            if items.is_empty() {
+                shape.empty_arrays.push(field.clone());
+            }
+            for item in items {
+                if !matches!(item, Value::Object(_) | Value::Array(_)) {
+                    record(shape, &field, FieldKind::of(item));
+                }
+                collect_shape(item, &field, shape);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Null never overrides a concrete kind, most of Minehut's fields are nullable.
+fn record(shape: &mut Shape, field: &str, kind: FieldKind) {
+    match shape.fields.get(field) {
+        Some(existing) if kind == FieldKind::Null || *existing == kind => {}
+        _ => {
+            shape.fields.insert(field.to_string(), kind);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn first_response_is_the_baseline() {
+        let detector = DriftDetector::new(DriftMode::Log);
+        let payload = json!({ "player_count": 12, "server_count": 3 });
+        assert!(detector
+            .observe("/network/simple_stats", &payload)
+            .is_none());
+        assert!(detector
+            .observe("/network/simple_stats", &payload)
+            .is_none());
+    }
+
+    #[test]
+    fn reports_added_and_retyped_fields() {
+        let detector = DriftDetector::new(DriftMode::Log);
+        detector.observe(
+            "/server/{name}",
+            &json!({ "server": { "name": "MockHub", "port": 25565 } }),
+        );
+        let report = detector
+            .observe(
+                "/server/{name}",
+                &json!({ "server": { "name": "MockHub", "port": "25565", "banner": "x" } }),
+            )
+            .unwrap();
+
+        assert_eq!(report.endpoint, "/server/{name}");
+        assert_eq!(
+            report.changes,
+            vec![
+                FieldDrift::Added {
+                    field: "server.banner".to_string(),
+                    kind: FieldKind::String,
+                },
+                FieldDrift::Retyped {
+                    field: "server.port".to_string(),
+                    was: FieldKind::Number,
+                    now: FieldKind::String,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn optional_fields_are_only_added_once() {
+        let detector = DriftDetector::new(DriftMode::Log);
+        let plain = json!({ "server": { "name": "MockHub" } });
+        let bannered = json!({ "server": { "name": "MockSurvival", "banner": "x" } });
+        detector.observe("/server/{name}", &plain);
+        assert!(detector.observe("/server/{name}", &bannered).is_some());
+        for _ in 0..MISSING_AFTER * 2 {
+            assert!(detector.observe("/server/{name}", &plain).is_none());
+            assert!(detector.observe("/server/{name}", &bannered).is_none());
+        }
+    }
+
+    #[test]
+    fn fields_are_missing_once_absent_long_enough() {
+        let detector = DriftDetector::new(DriftMode::Log);
+        let full = json!({ "name": "MockHub", "motd": "hi" });
+        let trimmed = json!({ "name": "MockHub" });
+        detector.observe("/server/{name}", &full);
+
+        let reports = (0..MISSING_AFTER * 2)
+            .filter_map(|_| detector.observe("/server/{name}", &trimmed))
+            .collect::<Vec<_>>();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(
+            reports[0].changes,
+            vec![FieldDrift::Missing {
+                field: "motd".to_string(),
+                kind: FieldKind::String,
+            }]
+        );
+
+        // coming back is not news, and restarts the count
+        assert!(detector.observe("/server/{name}", &full).is_none());
+        for _ in 1..MISSING_AFTER {
+            assert!(detector.observe("/server/{name}", &trimmed).is_none());
+        }
+        assert!(detector.observe("/server/{name}", &trimmed).is_some());
+    }
+
+    #[test]
+    fn unknown_fields_are_reported_once_even_in_the_baseline() {
+        let detector = DriftDetector::new(DriftMode::Log);
+        let payload = json!({ "server": { "name": "MockHub", "banner": "x" } });
+        detector.observe("/server/{name}", &payload);
+        let report = detector
+            .observe_unknown(
+                "/server/{name}",
+                [("server.banner".to_string(), &payload["server"]["banner"])],
+            )
+            .unwrap();
+        assert_eq!(
+            report.changes,
+            vec![FieldDrift::Added {
+                field: "server.banner".to_string(),
+                kind: FieldKind::String,
+            }]
+        );
+        assert!(detector
+            .observe_unknown(
+                "/server/{name}",
+                [("server.banner".to_string(), &payload["server"]["banner"])],
+            )
+            .is_none());
+        assert!(detector.observe("/server/{name}", &payload).is_none());
+    }
+
+    #[test]
+    fn added_fields_are_not_reported_again_as_unknown() {
+        let detector = DriftDetector::new(DriftMode::Log);
+        let payload = json!({ "name": "MockHub", "banner": "x" });
+        detector.observe("/server/{name}", &json!({ "name": "MockHub" }));
+        assert!(detector.observe("/server/{name}", &payload).is_some());
+        assert!(detector
+            .observe_unknown(
+                "/server/{name}",
+                [("banner".to_string(), &payload["banner"])]
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn first_items_of_empty_arrays_are_not_added() {
+        let detector = DriftDetector::new(DriftMode::Log);
+        detector.observe("/servers", &json!({ "servers": [] }));
+        assert!(detector
+            .observe(
+                "/servers",
+                &json!({ "servers": [{ "name": "MockHub", "tags": ["pvp"] }] })
+            )
+            .is_none());
+        let report = detector
+            .observe(
+                "/servers",
+                &json!({ "servers": [{ "name": "MockHub", "icon": "x" }] }),
+            )
+            .unwrap();
+        assert_eq!(
+            report.changes,
+            vec![FieldDrift::Added {
+                field: "servers[].icon".to_string(),
+                kind: FieldKind::String,
+            }]
+        );
+    }
+
+    #[test]
+    fn nulls_and_empty_arrays_are_not_drift() {
+        let detector = DriftDetector::new(DriftMode::Log);
+        detector.observe(
+            "/servers",
+            &json!({ "motd": "hi", "servers": [{ "name": "MockHub" }] }),
+        );
+        assert!(detector
+            .observe("/servers", &json!({ "motd": null, "servers": [] }))
+            .is_none());
+        assert!(detector
+            .observe(
+                "/servers",
+                &json!({ "motd": "hi", "servers": [{ "name": "MockHub" }] })
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn endpoints_keep_separate_baselines() {
+        let detector = DriftDetector::new(DriftMode::Log);
+        detector.observe("/plans", &json!({ "plans": [{ "name": "MH20" }] }));
+        assert!(detector
+            .observe("/icons", &json!({ "icons": [{ "price": 5 }] }))
+            .is_none());
+    }
+
+    #[test]
+    fn callback_receives_the_report() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let callback_seen = Arc::clone(&seen);
+        let detector = DriftDetector::new(DriftMode::Callback(Arc::new(move |report| {
+            callback_seen.lock().unwrap().push(report.changes.clone());
+        })));
+        detector.observe("/plans", &json!({ "name": "MH20" }));
+        detector.observe("/plans", &json!({ "name": "MH20", "price": 5 }));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![vec![FieldDrift::Added {
+                field: "price".to_string(),
+                kind: FieldKind::Number,
+            }]]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::convert::Infallible;
+use std::str::FromStr;
+
+const PRODUCTION_HOST: &str = "api.minehut.com";
+const DEVELOPMENT_HOST: &str = "api.dev.minehut.com";
+const DEFAULT_SCHEME: &str = "https";
+
+/// Which deployment of Minehut's API a client talks to.
+///
+/// Parses from `prod`, `dev` or a base url, ex: `http://127.0.0.1:8080` for a local mock. Urls
+/// without a scheme use `https`.
+#[derive(
+    serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug, Default, PartialEq, Eq,
+)]
+#[serde(from = "String", into = "String")]
+pub enum Environment {
+    Production,
+    #[default]
+    Development,
+    Custom {
+        scheme: String,
+        host: String,
+    },
+}
+
+impl Environment {
+    /// An environment at `url`, ex: `localhost:8080` or `http://127.0.0.1:8080`.
+    ///
+    /// Urls naming a preset's host over `https` become that preset.
+    pub fn custom<S: AsRef<str>>(url: S) -> Self {
+        let url = url.as_ref().trim();
+        let (scheme, host) = url.split_once("://").unwrap_or((DEFAULT_SCHEME, url));
+        let host = host.trim_end_matches('/');
+        match (scheme, host) {
+            (DEFAULT_SCHEME, PRODUCTION_HOST) => Environment::Production,
+            (DEFAULT_SCHEME, DEVELOPMENT_HOST) => Environment::Development,
+            _ => Environment::Custom {
+                scheme: scheme.to_string(),
+                host: host.to_string(),
+            },
+        }
+    }
+
+    /// The environment named by `MINEHUT_URL`, falling back to the dev api.
+    pub fn from_env() -> Self {
+        std::env::var("MINEHUT_URL")
+            .map(Environment::from)
+            .unwrap_or_default()
+    }
+
+    pub fn scheme(&self) -> &str {
+        match self {
+            Environment::Production | Environment::Development => DEFAULT_SCHEME,
+            Environment::Custom { scheme, .. } => scheme,
+        }
+    }
+
+    /// The api host, ex: `api.minehut.com`.
+    pub fn host(&self) -> &str {
+        match self {
+            Environment::Production => PRODUCTION_HOST,
+            Environment::Development => DEVELOPMENT_HOST,
+            Environment::Custom { host, .. } => host,
+        }
+    }
+
+    /// The same host over `scheme`.
+    pub fn with_scheme<S: AsRef<str>>(&self, scheme: S) -> Self {
+        Environment::custom(format!("{}://{}", scheme.as_ref(), self.host()))
+    }
+
+    pub fn base_url(&self) -> String {
+        format!("{}://{}", self.scheme(), self.host())
+    }
+
+    /// The full url of `path`, ex: `https://api.minehut.com/network/simple_stats`.
+    pub fn url_for<S: AsRef<str>>(&self, path: S) -> String {
+        format!("{}{}", self.base_url(), path.as_ref())
+    }
+}
+
+impl From<&str> for Environment {
+    fn from(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "prod" | "production" => Environment::Production,
+            "dev" | "development" => Environment::Development,
+            _ => Environment::custom(value),
+        }
+    }
+}
+
+impl From<String> for Environment {
+    fn from(value: String) -> Self {
+        Environment::from(value.as_str())
+    }
+}
+
+impl FromStr for Environment {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Environment::from(value))
+    }
+}
+
+impl From<Environment> for String {
+    fn from(environment: Environment) -> Self {
+        environment.to_string()
+    }
+}
+
+impl std::fmt::Display for Environment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Environment::Production => f.write_str("prod"),
+            Environment::Development => f.write_str("dev"),
+            Environment::Custom { .. } => f.write_str(&self.base_url()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(scheme: &str, host: &str) -> Environment {
+        Environment::Custom {
+            scheme: scheme.to_string(),
+            host: host.to_string(),
+        }
+    }
+
+    #[test]
+    fn names_and_urls_parse() {
+        let cases = [
+            ("prod", Environment::Production),
+            ("Production", Environment::Production),
+            (" PROD ", Environment::Production),
+            ("dev", Environment::Development),
+            ("development", Environment::Development),
+            ("api.minehut.com", Environment::Production),
+            ("https://api.minehut.com/", Environment::Production),
+            ("api.dev.minehut.com", Environment::Development),
+            ("http://api.minehut.com", custom("http", "api.minehut.com")),
+            ("localhost:8080", custom("https", "localhost:8080")),
+            ("http://127.0.0.1:8080", custom("http", "127.0.0.1:8080")),
+            ("http://127.0.0.1:8080/", custom("http", "127.0.0.1:8080")),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(Environment::from(input), expected, "{input}");
+            assert_eq!(input.parse::<Environment>().unwrap(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn custom_urls_keep_their_scheme() {
+        let environment = Environment::custom("http://127.0.0.1:8080");
+        assert_eq!(environment.scheme(), "http");
+        assert_eq!(environment.host(), "127.0.0.1:8080");
+        assert_eq!(
+            environment.url_for("/network/simple_stats"),
+            "http://127.0.0.1:8080/network/simple_stats"
+        );
+        assert_eq!(
+            Environment::Production.with_scheme("http"),
+            custom("http", "api.minehut.com")
+        );
+        assert_eq!(
+            Environment::custom("http://api.minehut.com").with_scheme("https"),
+            Environment::Production
+        );
+    }
+
+    #[test]
+    fn serde_round_trips_through_strings() {
+        let cases = [
+            ("\"prod\"", Environment::Production),
+            ("\"dev\"", Environment::Development),
+            ("\"api.minehut.com\"", Environment::Production),
+            (
+                "\"http://127.0.0.1:8080\"",
+                custom("http", "127.0.0.1:8080"),
+            ),
+        ];
+        for (json, expected) in cases {
+            let environment = serde_json::from_str::<Environment>(json).unwrap();
+            assert_eq!(environment, expected, "{json}");
+            let written = serde_json::to_string(&environment).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Environment>(&written).unwrap(),
+                expected
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&Environment::Production).unwrap(),
+            "\"prod\""
+        );
+        assert!(serde_json::from_str::<Environment>("8080").is_err());
+    }
+}
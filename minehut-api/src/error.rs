@@ -0,0 +1,91 @@
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+pub type Result<T> = std::result::Result<T, MinehutError>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MinehutError {
+    #[error("Failed to build the Minehut client.")]
+    Build(#[source] reqwest::Error),
+    #[error("Failed to reach Minehut's API for call {path}.")]
+    Transport {
+        path: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("Minehut's API responded with {status} for call {path}... {body}")]
+    Status {
+        path: String,
+        status: u16,
+        body: String,
+    },
+    #[error("Minehut's API rate limited call {path}.")]
+    RateLimited {
+        path: String,
+        retry_after: Option<Duration>,
+    },
+    #[error("Minehut's API refused the session for call {path}.")]
+    Unauthorized { path: String },
+    #[error("Minehut's API found nothing for call {path}.")]
+    NotFound { path: String },
+    #[error("The cassette has no recorded response for {method} {path}.")]
+    NotRecorded { method: String, path: String },
+    #[error("Failed to encode the request for call {path}... {message}")]
+    Encode { path: String, message: String },
+    #[error("Failed to decode `{field}` for call {path}... {payload}")]
+    Decode {
+        path: String,
+        /// The path inside the payload which failed to decode, ex: `server.creation`.
+        field: String,
+        payload: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+impl MinehutError {
+    /// The api path of the call which failed, if there was one.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            MinehutError::Build(_) => None,
+            MinehutError::Transport { path, .. }
+            | MinehutError::Status { path, .. }
+            | MinehutError::RateLimited { path, .. }
+            | MinehutError::Unauthorized { path }
+            | MinehutError::NotFound { path }
+            | MinehutError::NotRecorded { path, .. }
+            | MinehutError::Encode { path, .. }
+            | MinehutError::Decode { path, .. } => Some(path),
+        }
+    }
+
+    /// A short name for the kind of failure, ex: `timeout` or `not_found`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MinehutError::Build(_) => "build",
+            MinehutError::Transport { .. } if self.is_timeout() => "timeout",
+            MinehutError::Transport { .. } => "transport",
+            MinehutError::Status { .. } => "status",
+            MinehutError::RateLimited { .. } => "rate_limited",
+            MinehutError::Unauthorized { .. } => "unauthorized",
+            MinehutError::NotFound { .. } => "not_found",
+            MinehutError::NotRecorded { .. } => "not_recorded",
+            MinehutError::Encode { .. } => "encode",
+            MinehutError::Decode { .. } => "decode",
+        }
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, MinehutError::Transport { source, .. } if source.is_timeout())
+    }
+}
+
+pub(crate) fn decode<T: DeserializeOwned>(path: &str, payload: &str) -> Result<T> {
+    let deserializer = &mut serde_json::Deserializer::from_str(payload);
+    serde_path_to_error::deserialize(deserializer).map_err(|err| MinehutError::Decode {
+        path: path.to_string(),
+        field: err.path().to_string(),
+        payload: payload.to_string(),
+        source: err.into_inner(),
+    })
+}
@@ -1,2 +1,21 @@
+pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
+pub mod cassette;
+pub mod catalog;
+pub mod client;
+pub mod drift;
+pub mod environment;
+pub mod error;
+pub mod listing;
+pub mod manage;
+pub mod metrics;
 pub mod prelude;
 pub mod rest;
+pub mod retry;
+pub mod search;
+pub mod server;
+pub mod snapshot;
+pub mod user;
+pub mod watch;
@@ -0,0 +1,239 @@
+use crate::client::{default_client, MinehutClient};
+use crate::error::Result;
+use futures::stream::{self, Stream, TryStreamExt};
+use std::collections::HashMap;
+
+const LIST_PAGE_SIZE: usize = 100;
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct ServerListResponse {
+    pub servers: Vec<ServerListing>,
+    pub total_players: Option<usize>,
+    pub total_servers: Option<usize>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct ServerListing {
+    #[serde(rename = "staticInfo")]
+    pub static_info: Option<ServerStaticInfo>,
+    #[serde(rename = "maxPlayers")]
+    pub max_players: Option<f64>,
+    pub name: Option<String>,
+    pub motd: Option<String>,
+    pub icon: Option<String>,
+    #[serde(rename = "playerData")]
+    pub player_data: Option<ServerPlayerData>,
+    pub connectable: Option<bool>,
+    pub visibility: Option<bool>,
+    #[serde(rename = "allCategories")]
+    pub all_categories: Option<Vec<String>>,
+    #[serde(rename = "usingCosmetics")]
+    pub using_cosmetics: Option<bool>,
+    pub author: Option<String>,
+    #[serde(rename = "authorRank")]
+    pub author_rank: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct ServerStaticInfo {
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "serverPlan")]
+    pub server_plan: Option<String>,
+    #[serde(rename = "serviceStartDate")]
+    pub service_start_date: Option<f64>,
+    pub platform: Option<String>,
+    #[serde(rename = "planMaxPlayers")]
+    pub plan_max_players: Option<f64>,
+    #[serde(rename = "planRam")]
+    pub plan_ram: Option<f64>,
+    #[serde(rename = "alwaysOnline")]
+    pub always_online: Option<bool>,
+    #[serde(rename = "rawPlan")]
+    pub raw_plan: Option<String>,
+    #[serde(rename = "connectedServers")]
+    pub connected_servers: Option<Vec<String>>,
+    pub server_version_type: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct ServerPlayerData {
+    #[serde(rename = "playerCount")]
+    pub player_count: Option<f64>,
+    #[serde(rename = "timeNoPlayers")]
+    pub time_no_players: Option<f64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl ServerListing {
+    pub fn id(&self) -> Option<&str> {
+        self.static_info.as_ref().map(|info| info.id.as_str())
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.player_data
+            .as_ref()
+            .and_then(|data| data.player_count)
+            .unwrap_or(0.0) as usize
+    }
+
+    pub fn platform(&self) -> Option<&str> {
+        self.static_info.as_ref()?.platform.as_deref()
+    }
+
+    pub fn server_version_type(&self) -> Option<&str> {
+        self.static_info.as_ref()?.server_version_type.as_deref()
+    }
+}
+
+/// The page of `/servers` to fetch.
+#[derive(serde_derive::Serialize, Clone, Copy, Debug)]
+pub struct ServerListQuery {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// Client-side filters applied to every listed server, all string matches ignore case.
+#[derive(Clone, Debug, Default)]
+pub struct ServerListFilter {
+    category: Option<String>,
+    platform: Option<String>,
+    version_type: Option<String>,
+    min_players: usize,
+}
+
+impl ServerListFilter {
+    pub fn category<S: Into<String>>(mut self, category: S) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    pub fn platform<S: Into<String>>(mut self, platform: S) -> Self {
+        self.platform = Some(platform.into());
+        self
+    }
+
+    pub fn version_type<S: Into<String>>(mut self, version_type: S) -> Self {
+        self.version_type = Some(version_type.into());
+        self
+    }
+
+    pub fn min_players(mut self, min_players: usize) -> Self {
+        self.min_players = min_players;
+        self
+    }
+
+    pub fn matches(&self, server: &ServerListing) -> bool {
+        fn eq(expected: &Option<String>, actual: Option<&str>) -> bool {
+            match expected {
+                None => true,
+                Some(expected) => {
+                    actual.is_some_and(|actual| actual.eq_ignore_ascii_case(expected))
+                }
+            }
+        }
+
+        let in_category = match &self.category {
+            None => true,
+            Some(category) => server
+                .all_categories
+                .iter()
+                .flatten()
+                .any(|actual| actual.eq_ignore_ascii_case(category)),
+        };
+
+        in_category
+            && eq(&self.platform, server.platform())
+            && eq(&self.version_type, server.server_version_type())
+            && server.player_count() >= self.min_players
+    }
+}
+
+/// Where the page after one of `page_len` servers at `offset` starts, `None` once a short page
+/// or Minehut's `total` shows the list is exhausted.
+fn next_page_offset(offset: usize, page_len: usize, total: Option<usize>) -> Option<usize> {
+    let next_offset = offset + page_len;
+    let exhausted = page_len < LIST_PAGE_SIZE || total.is_some_and(|total| next_offset >= total);
+    (!exhausted).then_some(next_offset)
+}
+
+impl MinehutClient {
+    pub async fn get_server_list_page(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<ServerListResponse> {
+        let page = self
+            .call("/servers")
+            .query(&ServerListQuery { offset, limit })
+            .get::<ServerListResponse>()
+            .await?;
+        self.observe_extra("/servers", "", &page.extra);
+        for server in &page.servers {
+            self.observe_extra("/servers", "servers[].", &server.extra);
+        }
+        Ok(page)
+    }
+
+    /// Streams every online server matching `filter`, fetching pages as the stream is polled.
+    pub fn list_servers(
+        &self,
+        filter: ServerListFilter,
+    ) -> impl Stream<Item = Result<ServerListing>> + Send + 'static {
+        let client = self.clone();
+        stream::try_unfold(Some(0usize), move |offset| {
+            let client = client.clone();
+            async move {
+                let offset = match offset {
+                    Some(offset) => offset,
+                    None => return Ok(None),
+                };
+
+                let page = client.get_server_list_page(offset, LIST_PAGE_SIZE).await?;
+                let next = next_page_offset(offset, page.servers.len(), page.total_servers);
+                Ok(Some((stream::iter(page.servers.into_iter().map(Ok)), next)))
+            }
+        })
+        .try_flatten()
+        .try_filter(move |server| futures::future::ready(filter.matches(server)))
+    }
+}
+
+pub fn list_servers(
+    filter: ServerListFilter,
+) -> impl Stream<Item = Result<ServerListing>> + Send + 'static {
+    default_client().list_servers(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_pages_end_the_list() {
+        assert_eq!(next_page_offset(0, 40, Some(40)), None);
+        assert_eq!(next_page_offset(200, 40, None), None);
+        assert_eq!(next_page_offset(0, 0, None), None);
+    }
+
+    #[test]
+    fn full_pages_continue_until_the_total() {
+        assert_eq!(next_page_offset(0, LIST_PAGE_SIZE, Some(250)), Some(100));
+        assert_eq!(next_page_offset(100, LIST_PAGE_SIZE, Some(200)), None);
+        // a total which shrank while paging still ends the list
+        assert_eq!(next_page_offset(100, LIST_PAGE_SIZE, Some(150)), None);
+    }
+
+    #[test]
+    fn without_a_total_full_pages_always_continue() {
+        assert_eq!(next_page_offset(0, LIST_PAGE_SIZE, None), Some(100));
+        assert_eq!(next_page_offset(100, LIST_PAGE_SIZE, None), Some(200));
+    }
+}
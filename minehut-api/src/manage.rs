@@ -0,0 +1,228 @@
+//! Managing servers the client's [`Session`] owns.
+//!
+//! Every call here needs a logged in client, ex: one built with
+//! [`MinehutClientBuilder::session`], and fails with [`MinehutError::Unauthorized`] otherwise.
+//!
+//! [`Session`]: crate::auth::Session
+//! [`MinehutClientBuilder::session`]: crate::client::MinehutClientBuilder::session
+//! [`MinehutError::Unauthorized`]: crate::error::MinehutError::Unauthorized
+
+use crate::client::MinehutClient;
+use crate::error::Result;
+use crate::rest::ServerByNameResponse;
+use crate::server::ServiceState;
+use serde::de::IgnoredAny;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+
+/// The `server.properties` style settings of a server, keyed by name, ex: `max_players`.
+pub type ServerProperties = BTreeMap<String, Value>;
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct ServerStatus {
+    pub online: Option<bool>,
+    pub service_online: Option<bool>,
+    pub status: Option<ServiceState>,
+    pub starting: Option<bool>,
+    pub stopping: Option<bool>,
+    pub player_count: Option<f64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl ServerStatus {
+    pub fn is_online(&self) -> bool {
+        self.status == Some(ServiceState::Online) || self.online.unwrap_or(false)
+    }
+
+    /// Whether the server is between states, ex: still starting up.
+    pub fn is_transitioning(&self) -> bool {
+        matches!(
+            self.status,
+            Some(ServiceState::Starting | ServiceState::Stopping)
+        ) || self.starting.unwrap_or(false)
+            || self.stopping.unwrap_or(false)
+    }
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+struct WrappedStatus {
+    status: ServerStatus,
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct ServerData {
+    #[serde(flatten)]
+    pub server: ServerByNameResponse,
+    #[serde(default)]
+    pub server_properties: ServerProperties,
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+struct WrappedServerData {
+    server: ServerData,
+}
+
+#[derive(serde_derive::Serialize)]
+struct PropertyEdit<'a, V: Serialize> {
+    field: &'a str,
+    value: V,
+}
+
+#[derive(serde_derive::Serialize)]
+struct MotdEdit<'a> {
+    motd: &'a str,
+}
+
+#[derive(serde_derive::Serialize)]
+struct VisibilityEdit {
+    visibility: bool,
+}
+
+/// A server as it was before and after a change made through [`OwnedServer::change`].
+#[derive(Clone, Debug)]
+pub struct ServerChange {
+    pub before: ServerByNameResponse,
+    pub after: ServerByNameResponse,
+}
+
+impl ServerChange {
+    /// Every top level field which differs, as `(field, before, after)`.
+    pub fn differences(&self) -> Vec<(String, Value, Value)> {
+        let fields = |server: &ServerByNameResponse| match serde_json::to_value(server) {
+            Ok(Value::Object(fields)) => fields,
+            _ => serde_json::Map::new(),
+        };
+        let before = fields(&self.before);
+        let mut after = fields(&self.after);
+
+        let mut differences = Vec::new();
+        for (field, old) in before {
+            let new = after.remove(&field).unwrap_or(Value::Null);
+            if old != new {
+                differences.push((field, old, new));
+            }
+        }
+        differences.extend(
+            after
+                .into_iter()
+                .map(|(field, new)| (field, Value::Null, new)),
+        );
+        differences
+    }
+}
+
+/// A server owned by the client's session, see [`MinehutClient::owned_server`].
+#[derive(Clone, Debug)]
+pub struct OwnedServer {
+    client: MinehutClient,
+    id: String,
+}
+
+impl OwnedServer {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub async fn info(&self) -> Result<ServerByNameResponse> {
+        self.client.get_server_by_id(&self.id).await
+    }
+
+    /// Where the server is in its lifecycle, ex: whether it finished starting.
+    pub async fn status(&self) -> Result<ServerStatus> {
+        Ok(self
+            .client
+            .call(format!("/server/{}/status", self.id))
+            .endpoint("/server/{id}/status")
+            .get::<WrappedStatus>()
+            .await?
+            .status)
+    }
+
+    /// The server along with its `server.properties` style settings.
+    pub async fn data(&self) -> Result<ServerData> {
+        Ok(self
+            .client
+            .call(format!("/server/{}/server_data", self.id))
+            .endpoint("/server/{id}/server_data")
+            .get::<WrappedServerData>()
+            .await?
+            .server)
+    }
+
+    pub async fn properties(&self) -> Result<ServerProperties> {
+        Ok(self.data().await?.server_properties)
+    }
+
+    /// Starts the server, waking it from hibernation if needed.
+    pub async fn start(&self) -> Result<()> {
+        self.action("start_service", None::<&()>).await
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        self.action("shutdown", None::<&()>).await
+    }
+
+    pub async fn restart(&self) -> Result<()> {
+        self.action("restart", None::<&()>).await
+    }
+
+    /// Stops the server and releases its service until it is next started.
+    pub async fn hibernate(&self) -> Result<()> {
+        self.action("destroy_service", None::<&()>).await
+    }
+
+    pub async fn set_property<V: Serialize>(&self, field: &str, value: V) -> Result<()> {
+        self.action(
+            "edit_server_properties",
+            Some(&PropertyEdit { field, value }),
+        )
+        .await
+    }
+
+    pub async fn set_motd(&self, motd: &str) -> Result<()> {
+        self.action("change_motd", Some(&MotdEdit { motd })).await
+    }
+
+    pub async fn set_visibility(&self, visibility: bool) -> Result<()> {
+        self.action("visibility", Some(&VisibilityEdit { visibility }))
+            .await
+    }
+
+    /// Runs `change` against this server, fetching it before and after, ex:
+    /// `server.change(|server| async move { server.set_motd("Back soon!").await })`.
+    pub async fn change<F, Fut>(&self, change: F) -> Result<ServerChange>
+    where
+        F: FnOnce(OwnedServer) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let before = self.info().await?;
+        change(self.clone()).await?;
+        let after = self.info().await?;
+        Ok(ServerChange { before, after })
+    }
+
+    async fn action<B: Serialize>(&self, action: &str, body: Option<&B>) -> Result<()> {
+        let mut call = self
+            .client
+            .call(format!("/server/{}/{action}", self.id))
+            .endpoint(format!("/server/{{id}}/{action}"));
+        if let Some(body) = body {
+            call = call.json(body);
+        }
+        call.post::<IgnoredAny>().await?;
+        Ok(())
+    }
+}
+
+impl MinehutClient {
+    /// A handle for managing the server with `_id` `id`, which the client's session must own.
+    pub fn owned_server<S: Into<String>>(&self, id: S) -> OwnedServer {
+        OwnedServer {
+            client: self.clone(),
+            id: id.into(),
+        }
+    }
+}
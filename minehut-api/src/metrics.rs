@@ -0,0 +1,393 @@
+use crate::error::MinehutError;
+use crate::rest::Method;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds of the latency buckets in milliseconds, slower calls land in a final open bucket.
+const BUCKET_BOUNDS_MS: [u64; 12] = [5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000, 30000];
+
+/// Where a call's response came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResponseSource {
+    Network,
+    Cache,
+    Cassette,
+}
+
+/// One attempt at a call, retries are recorded as separate attempts.
+#[derive(Clone, Debug)]
+pub struct RequestMetric<'a> {
+    pub method: &'a Method,
+    /// The endpoint the call belongs to, ex: `/server/{name}`.
+    pub endpoint: &'a str,
+    /// The response status, `None` if no response arrived.
+    pub status: Option<u16>,
+    /// From sending the request to decoding the response, leaving out time spent in the rate
+    /// limiter or logging in.
+    pub duration: Duration,
+    /// Size of the response body.
+    pub bytes: usize,
+    pub source: Option<ResponseSource>,
+    pub error: Option<&'a MinehutError>,
+}
+
+/// Sees every call made through a client, set with [`MinehutClientBuilder::metrics`].
+///
+/// Called inline with each call, so implementations should be quick.
+///
+/// [`MinehutClientBuilder::metrics`]: crate::client::MinehutClientBuilder::metrics
+pub trait MetricsObserver: Send + Sync {
+    fn record(&self, metric: &RequestMetric<'_>);
+}
+
+impl<F: Fn(&RequestMetric<'_>) + Send + Sync> MetricsObserver for F {
+    fn record(&self, metric: &RequestMetric<'_>) {
+        self(metric)
+    }
+}
+
+impl std::fmt::Debug for dyn MetricsObserver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MetricsObserver")
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LatencyHistogram {
+    counts: [u64; BUCKET_BOUNDS_MS.len() + 1],
+    count: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, duration: Duration) {
+        let bucket = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|bound| duration <= Duration::from_millis(*bound))
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.total += duration;
+        self.max = self.max.max(duration);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        u32::try_from(self.count)
+            .ok()
+            .filter(|count| *count > 0)
+            .map(|count| self.total / count)
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// The upper bound of the bucket holding the `quantile`th call, ex: `0.99` for the p99.
+    ///
+    /// Calls past the last bucket are estimated by the slowest call seen.
+    pub fn percentile(&self, quantile: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let target = ((self.count as f64) * quantile.clamp(0.0, 1.0))
+            .ceil()
+            .max(1.0) as u64;
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return Some(match BUCKET_BOUNDS_MS.get(bucket) {
+                    Some(bound) => Duration::from_millis(*bound).min(self.max),
+                    None => self.max,
+                });
+            }
+        }
+        Some(self.max)
+    }
+
+    /// Every bucket as `(upper bound, calls)`, the last bucket has no upper bound.
+    pub fn buckets(&self) -> Vec<(Option<Duration>, u64)> {
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(bucket, count)| {
+                let bound = BUCKET_BOUNDS_MS
+                    .get(bucket)
+                    .copied()
+                    .map(Duration::from_millis);
+                (bound, *count)
+            })
+            .collect()
+    }
+
+    fn merge(&mut self, other: &LatencyHistogram) {
+        for (count, other) in self.counts.iter_mut().zip(other.counts) {
+            *count += other;
+        }
+        self.count += other.count;
+        self.total += other.total;
+        self.max = self.max.max(other.max);
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct EndpointMetrics {
+    pub calls: u64,
+    pub errors: u64,
+    /// Calls served from the cache or a cassette, these are left out of `latency`.
+    pub cached: u64,
+    pub bytes: u64,
+    pub statuses: BTreeMap<u16, u64>,
+    /// Failed calls by the kind of error, ex: `timeout`.
+    pub error_kinds: BTreeMap<&'static str, u64>,
+    pub latency: LatencyHistogram,
+}
+
+impl EndpointMetrics {
+    /// The share of calls which failed, from `0.0` to `1.0`.
+    pub fn error_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.calls as f64
+        }
+    }
+
+    fn record(&mut self, metric: &RequestMetric<'_>) {
+        self.calls += 1;
+        self.bytes += metric.bytes as u64;
+        if let Some(status) = metric.status {
+            *self.statuses.entry(status).or_default() += 1;
+        }
+        if let Some(error) = metric.error {
+            self.errors += 1;
+            *self.error_kinds.entry(error.kind()).or_default() += 1;
+        }
+        match metric.source {
+            Some(ResponseSource::Cache | ResponseSource::Cassette) => self.cached += 1,
+            _ => self.latency.record(metric.duration),
+        }
+    }
+
+    fn merge(&mut self, other: &EndpointMetrics) {
+        self.calls += other.calls;
+        self.errors += other.errors;
+        self.cached += other.cached;
+        self.bytes += other.bytes;
+        for (status, count) in &other.statuses {
+            *self.statuses.entry(*status).or_default() += count;
+        }
+        for (kind, count) in &other.error_kinds {
+            *self.error_kinds.entry(kind).or_default() += count;
+        }
+        self.latency.merge(&other.latency);
+    }
+}
+
+/// Keeps per endpoint counters and latency histograms in memory, ex:
+/// `MinehutClient::builder().metrics(Arc::new(ApiMetrics::new()))`.
+#[derive(Debug, Default)]
+pub struct ApiMetrics {
+    endpoints: Mutex<BTreeMap<String, EndpointMetrics>>,
+}
+
+impl ApiMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every endpoint seen so far, keyed like `GET /server/{name}`.
+    pub fn snapshot(&self) -> BTreeMap<String, EndpointMetrics> {
+        self.endpoints.lock().unwrap().clone()
+    }
+
+    /// Metrics for `endpoint` across every method, ex: `/network/simple_stats`.
+    pub fn endpoint(&self, endpoint: &str) -> Option<EndpointMetrics> {
+        let endpoints = self.endpoints.lock().unwrap();
+        let mut matching = endpoints
+            .iter()
+            .filter(|(key, _)| key.split_once(' ').map(|(_, path)| path) == Some(endpoint))
+            .map(|(_, metrics)| metrics)
+            .peekable();
+        matching.peek()?;
+        let mut total = EndpointMetrics::default();
+        matching.for_each(|metrics| total.merge(metrics));
+        Some(total)
+    }
+
+    /// Metrics across every endpoint.
+    pub fn total(&self) -> EndpointMetrics {
+        let mut total = EndpointMetrics::default();
+        for metrics in self.endpoints.lock().unwrap().values() {
+            total.merge(metrics);
+        }
+        total
+    }
+
+    pub fn reset(&self) {
+        self.endpoints.lock().unwrap().clear();
+    }
+}
+
+impl MetricsObserver for ApiMetrics {
+    fn record(&self, metric: &RequestMetric<'_>) {
+        self.endpoints
+            .lock()
+            .unwrap()
+            .entry(format!("{} {}", metric.method, metric.endpoint))
+            .or_default()
+            .record(metric);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn histogram(millis: &[u64]) -> LatencyHistogram {
+        let mut histogram = LatencyHistogram::default();
+        for millis in millis {
+            histogram.record(Duration::from_millis(*millis));
+        }
+        histogram
+    }
+
+    fn metric<'a>(
+        method: &'a Method,
+        endpoint: &'a str,
+        status: Option<u16>,
+        millis: u64,
+        source: Option<ResponseSource>,
+        error: Option<&'a MinehutError>,
+    ) -> RequestMetric<'a> {
+        RequestMetric {
+            method,
+            endpoint,
+            status,
+            duration: Duration::from_millis(millis),
+            bytes: 100,
+            source,
+            error,
+        }
+    }
+
+    #[test]
+    fn bucket_bounds_are_inclusive() {
+        let histogram = histogram(&[0, 5, 6, 30000, 30001]);
+        let buckets = histogram.buckets();
+        assert_eq!(buckets.len(), BUCKET_BOUNDS_MS.len() + 1);
+        assert_eq!(buckets[0], (Some(Duration::from_millis(5)), 2));
+        assert_eq!(buckets[1], (Some(Duration::from_millis(10)), 1));
+        assert_eq!(buckets[11], (Some(Duration::from_millis(30000)), 1));
+        assert_eq!(buckets[12], (None, 1));
+        assert_eq!(histogram.count(), 5);
+    }
+
+    #[test]
+    fn percentiles_are_bucket_bounds() {
+        // 90 calls of 20ms and 10 of 400ms
+        let samples = [20; 90].into_iter().chain([400; 10]).collect::<Vec<_>>();
+        let histogram = histogram(&samples);
+
+        assert_eq!(histogram.percentile(0.5), Some(Duration::from_millis(25)));
+        assert_eq!(histogram.percentile(0.9), Some(Duration::from_millis(25)));
+        assert_eq!(histogram.percentile(0.91), Some(Duration::from_millis(400)));
+        assert_eq!(histogram.percentile(0.99), Some(Duration::from_millis(400)));
+        assert_eq!(histogram.percentile(0.0), Some(Duration::from_millis(25)));
+        assert_eq!(histogram.max(), Some(Duration::from_millis(400)));
+        assert_eq!(histogram.mean(), Some(Duration::from_millis(58)));
+    }
+
+    #[test]
+    fn slow_calls_are_estimated_by_the_slowest() {
+        let histogram = histogram(&[1, 45000, 60000]);
+        assert_eq!(histogram.percentile(0.5), Some(Duration::from_secs(60)));
+        assert_eq!(histogram.percentile(0.1), Some(Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn empty_histograms_have_no_percentiles() {
+        let histogram = LatencyHistogram::default();
+        assert_eq!(histogram.percentile(0.5), None);
+        assert_eq!(histogram.mean(), None);
+        assert_eq!(histogram.max(), None);
+    }
+
+    #[test]
+    fn endpoints_aggregate_across_methods() {
+        let metrics = ApiMetrics::new();
+        let not_found = MinehutError::NotFound {
+            path: "/server/x".to_string(),
+        };
+        let server = "/server/{id}";
+        metrics.record(&metric(
+            &Method::GET,
+            server,
+            Some(200),
+            40,
+            Some(ResponseSource::Network),
+            None,
+        ));
+        metrics.record(&metric(
+            &Method::GET,
+            server,
+            Some(200),
+            0,
+            Some(ResponseSource::Cache),
+            None,
+        ));
+        metrics.record(&metric(
+            &Method::GET,
+            server,
+            Some(404),
+            20,
+            Some(ResponseSource::Network),
+            Some(&not_found),
+        ));
+        metrics.record(&metric(
+            &Method::POST,
+            server,
+            Some(200),
+            300,
+            Some(ResponseSource::Network),
+            None,
+        ));
+        metrics.record(&metric(
+            &Method::GET,
+            "/plans",
+            Some(200),
+            8,
+            Some(ResponseSource::Network),
+            None,
+        ));
+
+        let server = metrics.endpoint(server).unwrap();
+        assert_eq!(server.calls, 4);
+        assert_eq!(server.errors, 1);
+        assert_eq!(server.cached, 1);
+        assert_eq!(server.bytes, 400);
+        assert_eq!(server.statuses, BTreeMap::from([(200, 3), (404, 1)]));
+        assert_eq!(server.error_kinds, BTreeMap::from([(not_found.kind(), 1)]));
+        assert_eq!(server.error_rate(), 0.25);
+        // cached calls are left out of the latency
+        assert_eq!(server.latency.count(), 3);
+        assert_eq!(server.latency.max(), Some(Duration::from_millis(300)));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(
+            snapshot.keys().collect::<Vec<_>>(),
+            ["GET /plans", "GET /server/{id}", "POST /server/{id}"]
+        );
+        assert_eq!(metrics.total().calls, 5);
+        assert!(metrics.endpoint("/server").is_none());
+
+        metrics.reset();
+        assert_eq!(metrics.total().calls, 0);
+    }
+}
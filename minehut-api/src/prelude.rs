@@ -1 +1,16 @@
+pub use super::auth::{Credentials, Session, SessionToken};
+pub use super::cache::{CacheConfig, CacheStats, ResponseCache};
+pub use super::catalog::*;
+pub use super::client::*;
+pub use super::drift::*;
+pub use super::environment::Environment;
+pub use super::error::MinehutError;
+pub use super::listing::*;
+pub use super::manage::*;
+pub use super::metrics::*;
 pub use super::rest::*;
+pub use super::retry::*;
+pub use super::search::*;
+pub use super::server::*;
+pub use super::user::*;
+pub use super::watch::*;
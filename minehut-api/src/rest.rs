@@ -1,56 +1,471 @@
-use anyhow::Context;
+use crate::auth::{redact, SessionToken};
+use crate::cache::Validators;
+use crate::client::{default_client, MinehutClient};
+use crate::error::{decode, MinehutError, Result};
+use crate::metrics::{RequestMetric, ResponseSource};
+use crate::retry::RetryPolicy;
+use futures::StreamExt;
+use reqwest::header::{CONTENT_TYPE, IF_MODIFIED_SINCE, IF_NONE_MATCH, RETRY_AFTER};
+use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub use reqwest::Method;
+
+/// A response exactly as Minehut's API sent it, before any status handling or decoding.
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct RawResponse {
+    pub status: u16,
+    /// Header names are lowercase; repeated headers are joined with `, `.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl RawResponse {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    async fn read(response: reqwest::Response) -> reqwest::Result<Self> {
+        let status = response.status().as_u16();
+        let mut headers = BTreeMap::<String, String>::new();
+        for (name, value) in response.headers() {
+            if let Ok(value) = value.to_str() {
+                headers
+                    .entry(name.as_str().to_string())
+                    .and_modify(|existing| {
+                        existing.push_str(", ");
+                        existing.push_str(value);
+                    })
+                    .or_insert_with(|| value.to_string());
+            }
+        }
+        Ok(Self {
+            status,
+            headers,
+            body: response.text().await?,
+        })
+    }
+}
+
+/// A decoded response along with the status and headers it arrived with.
+#[derive(Clone, Debug)]
+pub struct ApiResponse<T> {
+    pub status: u16,
+    /// Header names are lowercase; repeated headers are joined with `, `.
+    pub headers: BTreeMap<String, String>,
+    pub data: T,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+}
 
 pub struct Call {
-    api: String,
+    client: MinehutClient,
     path: String,
+    endpoint: String,
+    retry: Option<RetryPolicy>,
+    body: Option<String>,
+    encode_error: Option<String>,
+    /// Time the current attempt spent in the rate limiter or logging in, left out of its latency.
+    waited: Mutex<Duration>,
 }
 
 impl Call {
     pub fn new<S: Into<String>>(path: S) -> Self {
+        default_client().call(path)
+    }
+
+    pub fn with_client<S: Into<String>>(client: MinehutClient, path: S) -> Self {
+        let path = path.into();
         Self {
-            api: std::env::var("MINEHUT_URL").unwrap_or_else(|_| "api.dev.minehut.com".to_string()),
-            path: path.into(),
+            client,
+            endpoint: path.split('?').next().unwrap_or_default().to_string(),
+            path,
+            retry: None,
+            body: None,
+            encode_error: None,
+            waited: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Names the endpoint this call belongs to, ex: `/server/{name}`.
+    ///
+    /// Calls to the same endpoint share drift baselines; defaults to the path without its query.
+    pub fn endpoint<S: Into<String>>(mut self, endpoint: S) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Overrides the client's retry policy for this call.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Appends `query` to the path, ex: a struct with `by_name: true` renamed to `byName`.
+    ///
+    /// `None` fields are left out.
+    pub fn query<Q: Serialize + ?Sized>(mut self, query: &Q) -> Self {
+        match serde_urlencoded::to_string(query) {
+            Ok(query) if query.is_empty() => {}
+            Ok(query) => {
+                self.path
+                    .push(if self.path.contains('?') { '&' } else { '?' });
+                self.path.push_str(&query);
+            }
+            Err(err) => self.encode_error = Some(err.to_string()),
+        }
+        self
+    }
+
+    /// Sends `body` as JSON with the request.
+    pub fn json<B: Serialize + ?Sized>(mut self, body: &B) -> Self {
+        match serde_json::to_string(body) {
+            Ok(body) => self.body = Some(body),
+            Err(err) => self.encode_error = Some(err.to_string()),
         }
+        self
     }
 
-    pub async fn get<T: DeserializeOwned>(&self) -> anyhow::Result<T> {
-        let full_path = format!("https://{}{}", self.api, self.path);
-        log::debug!(target: "MinehutAPI", "Calling API with path: {}", full_path);
-        let response = reqwest::get(full_path)
-            .await?
-            .text().await?;
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(self.send::<T>(Method::GET).await?.data)
+    }
 
-        serde_json::from_str(&*response).context(format!(
-            "Failed to decode data as type T for call {}... {}",
-            self.path,
-            &*response
-        ))
+    pub async fn post<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(self.send::<T>(Method::POST).await?.data)
+    }
+
+    pub async fn put<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(self.send::<T>(Method::PUT).await?.data)
+    }
+
+    pub async fn patch<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(self.send::<T>(Method::PATCH).await?.data)
+    }
+
+    pub async fn delete<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(self.send::<T>(Method::DELETE).await?.data)
+    }
+
+    /// Sends the call with `method`, keeping the response status and headers.
+    ///
+    /// Requests which are not idempotent, ex: `POST`, are only retried after being rate limited
+    /// since Minehut never saw them.
+    pub async fn send<T: DeserializeOwned>(&self, method: Method) -> Result<ApiResponse<T>> {
+        let policy = self.retry.as_ref().unwrap_or(self.client.retry_policy());
+        let mut attempt = 1;
+        loop {
+            match self.send_once(&method).await {
+                Err(err)
+                    if policy.should_retry(&err, attempt)
+                        && (method.is_idempotent()
+                            || matches!(err, MinehutError::RateLimited { .. })) =>
+                {
+                    let delay = policy.delay_for(&err, attempt);
+                    log::debug!(
+                        target: "MinehutAPI",
+                        "Retrying call {} {} in {}ms after attempt {}: {}",
+                        method,
+                        redact(&self.path),
+                        delay.as_millis(),
+                        attempt,
+                        err
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send_once<T: DeserializeOwned>(&self, method: &Method) -> Result<ApiResponse<T>> {
+        *self.waited.lock().unwrap() = Duration::ZERO;
+        let started = Instant::now();
+        let exchanged = self.authorized_exchange(method).await;
+        let (status, bytes, source) = match &exchanged {
+            Ok((response, source)) => (Some(response.status), response.body.len(), Some(*source)),
+            Err(_) => (None, 0, None),
+        };
+        let result = exchanged.and_then(|(response, _)| self.handle(method, response));
+
+        if let Some(metrics) = self.client.metrics() {
+            metrics.record(&RequestMetric {
+                method,
+                endpoint: &self.endpoint,
+                status,
+                duration: started
+                    .elapsed()
+                    .saturating_sub(*self.waited.lock().unwrap()),
+                bytes,
+                source,
+                error: result.as_ref().err(),
+            });
+        }
+        result
+    }
+
+    /// Exchanges the call with the session's token, logging in again once if it is refused.
+    async fn authorized_exchange(&self, method: &Method) -> Result<(RawResponse, ResponseSource)> {
+        let token = match self.client.session() {
+            Some(session) => self.waiting(session.current(&self.client)).await?,
+            None => None,
+        };
+        let mut exchanged = self.exchange(method, token.as_ref()).await?;
+
+        if exchanged.0.status == StatusCode::UNAUTHORIZED.as_u16() {
+            if let Some(session) = self.client.session() {
+                if self
+                    .waiting(session.refresh(&self.client, token.as_ref()))
+                    .await?
+                {
+                    let token = session.token().await;
+                    exchanged = self.exchange(method, token.as_ref()).await?;
+                }
+            }
+        }
+        Ok(exchanged)
+    }
+
+    /// Runs `future`, counting the time it takes as waiting rather than latency.
+    async fn waiting<F: Future>(&self, future: F) -> F::Output {
+        let started = Instant::now();
+        let output = future.await;
+        *self.waited.lock().unwrap() += started.elapsed();
+        output
+    }
+
+    fn handle<T: DeserializeOwned>(
+        &self,
+        method: &Method,
+        response: RawResponse,
+    ) -> Result<ApiResponse<T>> {
+        if response.status == StatusCode::UNAUTHORIZED.as_u16() {
+            return Err(MinehutError::Unauthorized {
+                path: self.path.clone(),
+            });
+        }
+
+        if response.status == StatusCode::TOO_MANY_REQUESTS.as_u16() {
+            let retry_after = response
+                .header(RETRY_AFTER.as_str())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            if let (Some(rate_limiter), Some(retry_after)) =
+                (self.client.rate_limiter(), retry_after)
+            {
+                // a huge Retry-After would otherwise stall every call on the client
+                rate_limiter.block_for(self.client.retry_policy().cap(retry_after));
+            }
+            return Err(MinehutError::RateLimited {
+                path: self.path.clone(),
+                retry_after,
+            });
+        }
+        if response.status == StatusCode::NOT_FOUND.as_u16() {
+            return Err(MinehutError::NotFound {
+                path: self.path.clone(),
+            });
+        }
+        if !(200..300).contains(&response.status) {
+            return Err(MinehutError::Status {
+                path: self.path.clone(),
+                status: response.status,
+                body: response.body,
+            });
+        }
+
+        if *method == Method::GET {
+            if let Some(drift_detector) = self.client.drift_detector() {
+                if let Ok(payload) = serde_json::from_str(&response.body) {
+                    drift_detector.observe(&self.endpoint, &payload);
+                }
+            }
+        } else if let Some(cache) = self.client.cache() {
+            cache.invalidate_resource(&self.path);
+        }
+
+        // empty bodies, ex: from a 204, decode like `null` so `()` and `Option` work
+        let body = if response.body.trim().is_empty() {
+            "null"
+        } else {
+            &response.body
+        };
+        Ok(ApiResponse {
+            data: decode(&self.path, body)?,
+            status: response.status,
+            headers: response.headers,
+        })
+    }
+
+    /// Sends the request, or serves it from the client's cassette or cache.
+    ///
+    /// Calls made with a session token skip the cache, so one session never sees another's data.
+    async fn exchange(
+        &self,
+        method: &Method,
+        token: Option<&SessionToken>,
+    ) -> Result<(RawResponse, ResponseSource)> {
+        if let Some(message) = &self.encode_error {
+            return Err(MinehutError::Encode {
+                path: self.path.clone(),
+                message: message.clone(),
+            });
+        }
+
+        if let Some(cassette) = self
+            .client
+            .cassette()
+            .filter(|cassette| cassette.is_replaying())
+        {
+            return cassette
+                .replay_next(method.as_str(), &self.path)
+                .map(|response| (response, ResponseSource::Cassette))
+                .ok_or_else(|| MinehutError::NotRecorded {
+                    method: method.to_string(),
+                    path: self.path.clone(),
+                });
+        }
+
+        let cache = match self.client.cache() {
+            Some(cache)
+                if *method == Method::GET
+                    && token.is_none()
+                    && cache.is_enabled_for(&self.endpoint) =>
+            {
+                cache
+            }
+            _ => {
+                let response = self
+                    .exchange_uncached(method, token, Validators::default())
+                    .await?;
+                return Ok((response, ResponseSource::Network));
+            }
+        };
+
+        if let Some(response) = cache.hit(&self.path, false) {
+            return Ok((response, ResponseSource::Cache));
+        }
+        let _in_flight = cache.lock(&self.path).await;
+        if let Some(response) = cache.hit(&self.path, true) {
+            return Ok((response, ResponseSource::Cache));
+        }
+
+        let response = self
+            .exchange_uncached(method, None, cache.validators(&self.path))
+            .await?;
+        if response.status == StatusCode::NOT_MODIFIED.as_u16() {
+            if let Some(response) = cache.revalidate(&self.path) {
+                return Ok((response, ResponseSource::Network));
+            }
+        }
+        cache.store(&self.endpoint, &self.path, &response);
+        Ok((response, ResponseSource::Network))
+    }
+
+    async fn exchange_uncached(
+        &self,
+        method: &Method,
+        token: Option<&SessionToken>,
+        validators: Validators,
+    ) -> Result<RawResponse> {
+        if let Some(rate_limiter) = self.client.rate_limiter() {
+            self.waiting(rate_limiter.acquire()).await;
+        }
+
+        let full_path = self.client.url_for(&self.path);
+        log::debug!(
+            target: "MinehutAPI",
+            "Calling API with {} {}{}",
+            method,
+            redact(&full_path),
+            if token.is_some() { " (authenticated)" } else { "" }
+        );
+        let mut request = self.client.http().request(method.clone(), full_path);
+        for (name, value) in token.map(SessionToken::headers).unwrap_or_default() {
+            request = request.header(name, value);
+        }
+        if let Some(body) = &self.body {
+            request = request
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.clone());
+        }
+        if let Some(etag) = validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|source| self.transport_error(source))?;
+        let response = RawResponse::read(response)
+            .await
+            .map_err(|source| self.transport_error(source))?;
+
+        if let Some(cassette) = self.client.cassette() {
+            cassette.record_next(method.as_str(), &self.path, &response);
+        }
+        Ok(response)
+    }
+
+    fn transport_error(&self, source: reqwest::Error) -> MinehutError {
+        MinehutError::Transport {
+            path: self.path.clone(),
+            source,
+        }
     }
 }
 
 macro_rules! get {
     ($function:ident,$response:ty,$url:literal) => {
-        pub async fn $function() -> anyhow::Result<$response> {
-            Call::new($url)
-                .get::<$response>()
-                .await
+        impl MinehutClient {
+            pub async fn $function(&self) -> Result<$response> {
+                let response = self.call($url).get::<$response>().await?;
+                self.observe_extra($url, "", &response.extra);
+                Ok(response)
+            }
         }
-    }
+
+        pub async fn $function() -> Result<$response> {
+            default_client().$function().await
+        }
+    };
 }
 
-#[derive(serde_derive::Deserialize, Debug)]
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
 pub struct NetworkSimpleStatsResponse {
     pub player_count: usize,
     pub server_count: usize,
     pub server_max: usize,
     pub ram_count: usize,
     pub ram_max: usize,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 get!(get_simple_stats, NetworkSimpleStatsResponse, "/network/simple_stats");
 
-#[derive(serde_derive::Deserialize, Debug)]
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
 pub struct ServerByNameResponse {
     #[serde(rename = "_id")]
     pub id: String,
@@ -87,16 +502,129 @@ pub struct ServerByNameResponse {
     pub storage_node: Option<String>,
     pub suspended: Option<bool>,
     pub visibility: Option<bool>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
-#[derive(serde_derive::Deserialize, Debug)]
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
 pub struct WrappedServer {
-    server: ServerByNameResponse
+    server: Option<ServerByNameResponse>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(serde_derive::Serialize, Clone, Debug, Default)]
+pub struct ServerQuery {
+    #[serde(rename = "byName")]
+    pub by_name: bool,
+}
+
+/// Identifies a server either by its name or by its `_id`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ServerRef {
+    Name(String),
+    Id(String),
+}
+
+impl std::fmt::Display for ServerRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerRef::Name(name) => write!(f, "{name}"),
+            ServerRef::Id(id) => write!(f, "{id}"),
+        }
+    }
 }
 
-pub async fn get_server_by_name<S: Into<String> + std::fmt::Display>(server: S) -> anyhow::Result<ServerByNameResponse> {
-    Call::new(format!("/server/{server}?byName=true"))
-        .get::<WrappedServer>()
+impl MinehutClient {
+    pub async fn get_server_by_name<S: Into<String> + std::fmt::Display>(
+        &self,
+        server: S,
+    ) -> Result<ServerByNameResponse> {
+        self.get_wrapped_server(
+            self.call(format!("/server/{server}"))
+                .query(&ServerQuery { by_name: true })
+                .endpoint("/server/{name}"),
+        )
         .await
-        .map(|ws| ws.server)
+    }
+
+    pub async fn get_server_by_id<S: Into<String> + std::fmt::Display>(
+        &self,
+        id: S,
+    ) -> Result<ServerByNameResponse> {
+        self.get_wrapped_server(self.call(format!("/server/{id}")).endpoint("/server/{id}"))
+            .await
+    }
+
+    pub async fn get_server(&self, server: &ServerRef) -> Result<ServerByNameResponse> {
+        match server {
+            ServerRef::Name(name) => self.get_server_by_name(name).await,
+            ServerRef::Id(id) => self.get_server_by_id(id).await,
+        }
+    }
+
+    /// Resolves every server with at most `concurrency` calls in flight.
+    ///
+    /// Results are returned in the order the servers were given; one failure does not affect the
+    /// others.
+    pub async fn get_servers<I: IntoIterator<Item = ServerRef>>(
+        &self,
+        servers: I,
+        concurrency: usize,
+    ) -> Vec<(ServerRef, Result<ServerByNameResponse>)> {
+        futures::stream::iter(servers)
+            .map(|server| async move {
+                let response = self.get_server(&server).await;
+                (server, response)
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    async fn get_wrapped_server(&self, call: Call) -> Result<ServerByNameResponse> {
+        let wrapped = call.get::<WrappedServer>().await?;
+        self.observe_extra(&call.endpoint, "", &wrapped.extra);
+        let server = wrapped
+            .server
+            .ok_or_else(|| MinehutError::NotFound { path: call.path })?;
+        self.observe_extra(&call.endpoint, "server.", &server.extra);
+        Ok(server)
+    }
+
+    /// Reports the fields a model's `extra` caught under `prefix` as drift, once each.
+    pub(crate) fn observe_extra(
+        &self,
+        endpoint: &str,
+        prefix: &str,
+        extra: &HashMap<String, serde_json::Value>,
+    ) {
+        if let Some(drift_detector) = self.drift_detector() {
+            drift_detector.observe_unknown(
+                endpoint,
+                extra
+                    .iter()
+                    .map(|(field, value)| (format!("{prefix}{field}"), value)),
+            );
+        }
+    }
+}
+
+pub async fn get_server_by_name<S: Into<String> + std::fmt::Display>(
+    server: S,
+) -> Result<ServerByNameResponse> {
+    default_client().get_server_by_name(server).await
+}
+
+pub async fn get_server_by_id<S: Into<String> + std::fmt::Display>(
+    id: S,
+) -> Result<ServerByNameResponse> {
+    default_client().get_server_by_id(id).await
+}
+
+pub async fn get_servers<I: IntoIterator<Item = ServerRef>>(
+    servers: I,
+    concurrency: usize,
+) -> Vec<(ServerRef, Result<ServerByNameResponse>)> {
+    default_client().get_servers(servers, concurrency).await
 }
@@ -0,0 +1,305 @@
+use crate::error::MinehutError;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The slowest rate a [`RateLimiter`] allows, one request every 100 seconds.
+const MIN_PER_SECOND: f64 = 0.01;
+
+/// How a call is retried when Minehut's API fails transiently.
+///
+/// Transport failures, 5xx responses and rate limits are retried; anything else is returned
+/// immediately.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which only ever makes one attempt.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Total attempts including the first, clamped to at least one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry, doubled for every retry after.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub(crate) fn should_retry(&self, err: &MinehutError, attempt: u32) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        match err {
+            MinehutError::Transport { .. } | MinehutError::RateLimited { .. } => true,
+            MinehutError::Status { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// `delay`, or `max_delay` if that is shorter, ex: for a server's `Retry-After`.
+    pub(crate) fn cap(&self, delay: Duration) -> Duration {
+        delay.min(self.max_delay)
+    }
+
+    /// The delay before retrying after `attempt` failed with `err`, never more than `max_delay`.
+    pub(crate) fn delay_for(&self, err: &MinehutError, attempt: u32) -> Duration {
+        if let MinehutError::RateLimited {
+            retry_after: Some(retry_after),
+            ..
+        } = err
+        {
+            return self.cap(*retry_after);
+        }
+
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+        if self.jitter {
+            // equal jitter, never waits less than half the backoff
+            delay / 2 + delay.mul_f64(fastrand::f64() / 2.0)
+        } else {
+            delay
+        }
+    }
+}
+
+/// A token bucket shared by every call made through one client.
+#[derive(Debug)]
+pub struct RateLimiter {
+    per_second: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+    blocked_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Allows `per_second` requests on average with bursts of up to `burst` requests.
+    ///
+    /// Rates below one request every 100 seconds, including zero, are raised to it and the burst
+    /// is at least one request.
+    pub fn new(per_second: f64, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            per_second: per_second.max(MIN_PER_SECOND),
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                refilled_at: Instant::now(),
+                blocked_until: None,
+            }),
+        }
+    }
+
+    /// Waits until a request may be sent.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = self.try_acquire();
+            if wait.is_zero() {
+                return;
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Stops every caller from sending until `duration` has passed, ex: after a 429.
+    pub fn block_for(&self, duration: Duration) {
+        // too far out to represent, which no caller means to wait for anyway
+        let Some(until) = Instant::now().checked_add(duration) else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap();
+        if state.blocked_until.is_none_or(|blocked| blocked < until) {
+            state.blocked_until = Some(until);
+        }
+    }
+
+    /// Takes a token if one is available, otherwise returns how long to wait for one.
+    fn try_acquire(&self) -> Duration {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(blocked_until) = state.blocked_until {
+            if blocked_until > now {
+                return blocked_until - now;
+            }
+            state.blocked_until = None;
+        }
+
+        let elapsed = now.duration_since(state.refilled_at).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.per_second).min(self.burst);
+        state.refilled_at = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - state.tokens) / self.per_second)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_error() -> MinehutError {
+        MinehutError::Status {
+            path: "/network/simple_stats".to_string(),
+            status: 503,
+            body: String::new(),
+        }
+    }
+
+    fn rate_limited(retry_after: Option<Duration>) -> MinehutError {
+        MinehutError::RateLimited {
+            path: "/network/simple_stats".to_string(),
+            retry_after,
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max_delay() {
+        let policy = RetryPolicy::default()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(500))
+            .jitter(false);
+        let delays = (1..=5)
+            .map(|attempt| policy.delay_for(&server_error(), attempt))
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [100, 200, 400, 500, 500].map(Duration::from_millis));
+        assert_eq!(
+            policy.delay_for(&server_error(), u32::MAX),
+            Duration::from_millis(500)
+        );
+    }
+
+    #[test]
+    fn jitter_stays_within_half_to_full_backoff() {
+        let policy = RetryPolicy::default()
+            .base_delay(Duration::from_millis(200))
+            .max_delay(Duration::from_secs(10));
+        for attempt in 1..=4 {
+            let backoff = Duration::from_millis(200 << (attempt - 1));
+            for _ in 0..100 {
+                let delay = policy.delay_for(&server_error(), attempt);
+                assert!(delay >= backoff / 2 && delay <= backoff, "{delay:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn retry_after_is_used_and_capped() {
+        let policy = RetryPolicy::default()
+            .max_delay(Duration::from_secs(10))
+            .jitter(false);
+        assert_eq!(
+            policy.delay_for(&rate_limited(Some(Duration::from_secs(3))), 1),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            policy.delay_for(&rate_limited(Some(Duration::from_secs(3600))), 1),
+            Duration::from_secs(10)
+        );
+        // without a header it backs off like any other failure
+        assert_eq!(
+            policy.delay_for(&rate_limited(None), 2),
+            Duration::from_millis(500)
+        );
+    }
+
+    #[test]
+    fn only_transient_failures_are_retried() {
+        let policy = RetryPolicy::default().max_attempts(3);
+        assert!(policy.should_retry(&server_error(), 1));
+        assert!(policy.should_retry(&rate_limited(None), 2));
+        assert!(!policy.should_retry(&server_error(), 3));
+        assert!(!policy.should_retry(
+            &MinehutError::NotFound {
+                path: "/server/x".to_string()
+            },
+            1
+        ));
+    }
+
+    #[test]
+    fn tokens_refill_over_time() {
+        let limiter = RateLimiter::new(20.0, 2);
+        assert!(limiter.try_acquire().is_zero());
+        assert!(limiter.try_acquire().is_zero());
+        let wait = limiter.try_acquire();
+        assert!(
+            wait > Duration::ZERO && wait <= Duration::from_millis(50),
+            "{wait:?}"
+        );
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(limiter.try_acquire().is_zero());
+        assert!(!limiter.try_acquire().is_zero());
+    }
+
+    #[test]
+    fn blocking_delays_every_caller() {
+        let limiter = RateLimiter::new(100.0, 10);
+        limiter.block_for(Duration::from_secs(5));
+        let wait = limiter.try_acquire();
+        assert!(wait > Duration::from_secs(4) && wait <= Duration::from_secs(5));
+    }
+
+    #[test]
+    fn unrepresentable_blocks_are_ignored() {
+        let limiter = RateLimiter::new(100.0, 10);
+        limiter.block_for(Duration::MAX);
+        assert!(limiter.try_acquire().is_zero());
+    }
+
+    #[test]
+    fn zero_and_tiny_rates_are_clamped() {
+        for per_second in [0.0, -1.0, f64::MIN_POSITIVE, f64::NAN] {
+            let limiter = RateLimiter::new(per_second, 0);
+            assert!(limiter.try_acquire().is_zero());
+            assert!(limiter.try_acquire() <= Duration::from_secs(100));
+        }
+    }
+}
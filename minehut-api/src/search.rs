@@ -0,0 +1,249 @@
+//! Finding servers without knowing their exact name.
+//!
+//! Searches run over the server list, which only holds online servers, and stop after the first
+//! [`MAX_SEARCHED_SERVERS`] of them.
+
+use crate::client::{default_client, MinehutClient};
+use crate::error::{MinehutError, Result};
+use crate::listing::{ServerListFilter, ServerListing};
+use futures::{StreamExt, TryStreamExt};
+
+/// Fuzzy matches less similar than this are dropped.
+const MIN_SIMILARITY: f64 = 0.5;
+
+/// How many listed servers a search looks at, ten pages of `/servers`.
+pub const MAX_SEARCHED_SERVERS: usize = 1000;
+
+/// How a server's name matched the query, best first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    Exact,
+    Prefix,
+    Contains,
+    /// Within a few typos of the query.
+    Fuzzy,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchMatch {
+    pub server: ServerListing,
+    pub kind: MatchKind,
+    /// From `0.0` to `1.0`, matches are ranked by this.
+    pub score: f64,
+}
+
+impl SearchMatch {
+    pub fn name(&self) -> &str {
+        self.server.name.as_deref().unwrap_or_default()
+    }
+}
+
+/// How well `name` matches `query`, both compared in lowercase.
+pub fn match_name(query: &str, name: &str) -> Option<(MatchKind, f64)> {
+    let query = query.trim().to_lowercase();
+    let name_lower = name.to_lowercase();
+    if query.is_empty() || name_lower.is_empty() {
+        return None;
+    }
+
+    // how much of the name the query covers, so closer lengths rank higher
+    let coverage = query.chars().count() as f64 / name_lower.chars().count() as f64;
+    if name_lower == query {
+        return Some((MatchKind::Exact, 1.0));
+    }
+    if name_lower.starts_with(&query) {
+        return Some((MatchKind::Prefix, 0.8 + 0.15 * coverage));
+    }
+    if name_lower.contains(&query) {
+        return Some((MatchKind::Contains, 0.6 + 0.15 * coverage));
+    }
+
+    // typos in the whole name, or in the part of it the query was meant to match
+    let name_chars = name_lower.chars().collect::<Vec<_>>();
+    let window = query.chars().count().min(name_chars.len());
+    let best_part = name_chars
+        .windows(window)
+        .map(|part| similarity(&query, &part.iter().collect::<String>()))
+        .fold(0.0, f64::max);
+    let similarity = similarity(&query, &name_lower).max(best_part * 0.9);
+    (similarity >= MIN_SIMILARITY).then_some((MatchKind::Fuzzy, 0.6 * similarity))
+}
+
+/// Ranks every server in `servers` against `query`, best first, keeping at most `limit`.
+///
+/// Ties go to the server with more players.
+pub fn rank_servers<I: IntoIterator<Item = ServerListing>>(
+    query: &str,
+    servers: I,
+    limit: usize,
+) -> Vec<SearchMatch> {
+    let mut matches = servers
+        .into_iter()
+        .filter_map(|server| {
+            let (kind, score) = match_name(query, server.name.as_deref()?)?;
+            Some(SearchMatch {
+                server,
+                kind,
+                score,
+            })
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| b.server.player_count().cmp(&a.server.player_count()))
+            .then_with(|| a.name().cmp(b.name()))
+    });
+    matches.truncate(limit);
+    matches
+}
+
+/// `1.0` minus the edit distance between `a` and `b` relative to the longer of the two.
+fn similarity(a: &str, b: &str) -> f64 {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+impl MinehutClient {
+    /// Online servers whose name matches `query` by prefix, substring or a few typos, best first.
+    pub async fn search_servers(&self, query: &str, limit: usize) -> Result<Vec<SearchMatch>> {
+        let servers = self
+            .list_servers(ServerListFilter::default())
+            .take(MAX_SEARCHED_SERVERS)
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(rank_servers(query, servers, limit))
+    }
+
+    /// Whether no server, online or not, is called `name`, ignoring case.
+    pub async fn is_name_available(&self, name: &str) -> Result<bool> {
+        match self.get_server_by_name(name).await {
+            Ok(_) => Ok(false),
+            Err(MinehutError::NotFound { .. }) => Ok(true),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+pub async fn search_servers(query: &str, limit: usize) -> Result<Vec<SearchMatch>> {
+    default_client().search_servers(query, limit).await
+}
+
+pub async fn is_name_available(name: &str) -> Result<bool> {
+    default_client().is_name_available(name).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn listing(name: &str, players: usize) -> ServerListing {
+        serde_json::from_value(json!({
+            "name": name,
+            "playerData": { "playerCount": players },
+        }))
+        .unwrap()
+    }
+
+    fn names(matches: &[SearchMatch]) -> Vec<&str> {
+        matches.iter().map(SearchMatch::name).collect()
+    }
+
+    #[test]
+    fn match_kinds_rank_exact_then_prefix_contains_and_fuzzy() {
+        let (exact, exact_score) = match_name("skyblock", "SkyBlock").unwrap();
+        let (prefix, prefix_score) = match_name("sky", "SkyBlock").unwrap();
+        let (contains, contains_score) = match_name("block", "SkyBlock").unwrap();
+        let (fuzzy, fuzzy_score) = match_name("skyblcok", "SkyBlock").unwrap();
+        assert_eq!(
+            [exact, prefix, contains, fuzzy],
+            [
+                MatchKind::Exact,
+                MatchKind::Prefix,
+                MatchKind::Contains,
+                MatchKind::Fuzzy
+            ]
+        );
+        assert!(exact_score > prefix_score);
+        assert!(prefix_score > contains_score);
+        assert!(contains_score > fuzzy_score);
+    }
+
+    #[test]
+    fn matching_ignores_case_and_surrounding_whitespace() {
+        assert_eq!(
+            match_name("  MOCKHUB ", "mockhub"),
+            Some((MatchKind::Exact, 1.0))
+        );
+        assert_eq!(
+            match_name("mock", "MockHub").map(|(kind, _)| kind),
+            Some(MatchKind::Prefix)
+        );
+    }
+
+    #[test]
+    fn unrelated_and_empty_names_do_not_match() {
+        assert_eq!(match_name("skyblock", "Factions"), None);
+        assert_eq!(match_name("", "SkyBlock"), None);
+        assert_eq!(match_name("   ", "SkyBlock"), None);
+        assert_eq!(match_name("skyblock", ""), None);
+    }
+
+    #[test]
+    fn closer_lengths_rank_higher() {
+        let (_, short) = match_name("sky", "SkyBlock").unwrap();
+        let (_, long) = match_name("sky", "SkyBlockAdventures").unwrap();
+        assert!(short > long);
+    }
+
+    #[test]
+    fn ranks_best_first_and_truncates() {
+        let servers = vec![
+            listing("Factions", 40),
+            listing("MySkyBlock", 5),
+            listing("SkyBlock", 1),
+            listing("SkyBlcok", 90),
+            listing("SkyBlockPlus", 2),
+        ];
+        let ranked = rank_servers("skyblock", servers.clone(), 10);
+        assert_eq!(
+            names(&ranked),
+            ["SkyBlock", "SkyBlockPlus", "MySkyBlock", "SkyBlcok"]
+        );
+        assert_eq!(
+            names(&rank_servers("skyblock", servers, 2)),
+            ["SkyBlock", "SkyBlockPlus"]
+        );
+    }
+
+    #[test]
+    fn ties_go_to_more_players_then_name() {
+        let servers = vec![
+            listing("SkyB", 3),
+            listing("SkyA", 3),
+            listing("SkyC", 20),
+            listing("Unnamed", 0),
+        ];
+        assert_eq!(
+            names(&rank_servers("sky", servers, 10)),
+            ["SkyC", "SkyA", "SkyB"]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use crate::rest::ServerByNameResponse;
+use chrono::{DateTime, TimeZone, Utc};
+
+macro_rules! string_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $value:literal),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)+
+            /// A value this library doesn't know about yet, kept as Minehut sent it.
+            Other(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)+
+                    $name::Other(other) => other,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                $(if value.eq_ignore_ascii_case($value) {
+                    return $name::$variant;
+                })+
+                $name::Other(value.to_string())
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map(|value| $name::from(value.as_str()))
+            }
+        }
+    };
+}
+
+string_enum!(Platform {
+    Java => "java",
+    Bedrock => "bedrock",
+});
+
+string_enum!(ServerPlan {
+    Free => "FREE",
+    Daily => "DAILY",
+    Mh20 => "MH20",
+    Mh35 => "MH35",
+    Mh50 => "MH50",
+    Mh75 => "MH75",
+    MhUnlimited => "MHUNLIMITED",
+    Custom => "CUSTOM",
+});
+
+string_enum!(VersionType {
+    Vanilla => "VANILLA",
+    Paper => "PAPER",
+    Spigot => "SPIGOT",
+    Bukkit => "BUKKIT",
+    Forge => "FORGE",
+    Fabric => "FABRIC",
+});
+
+string_enum!(
+    /// Where an owned server is in its lifecycle, as `/server/{id}/status` reports it.
+    ServiceState {
+        Online => "ONLINE",
+        Offline => "OFFLINE",
+        Starting => "STARTING",
+        Stopping => "STOPPING",
+        Hibernating => "HIBERNATING",
+    }
+);
+
+/// A typed view of [`ServerByNameResponse`], missing values fall back to their defaults.
+///
+/// The raw response stays the lossless form; this is built from it with
+/// [`ServerByNameResponse::typed`].
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct Server {
+    pub id: String,
+    pub name: Option<String>,
+    pub name_lower: Option<String>,
+    pub owner: Option<String>,
+    pub motd: Option<String>,
+    pub online: bool,
+    pub visibility: bool,
+    pub suspended: bool,
+    pub proxy: bool,
+    pub player_count: u32,
+    pub max_players: u32,
+    pub port: Option<u16>,
+    pub backup_slots: u32,
+    pub credits_per_day: f64,
+    pub creation: Option<DateTime<Utc>>,
+    pub last_online: Option<DateTime<Utc>>,
+    pub platform: Option<Platform>,
+    pub server_plan: Option<ServerPlan>,
+    pub active_server_plan: Option<ServerPlan>,
+    pub raw_plan: Option<String>,
+    pub server_version_type: Option<VersionType>,
+    pub categories: Vec<String>,
+    pub inherited_categories: Vec<String>,
+    pub connected_servers: Vec<String>,
+    pub purchased_icons: Vec<String>,
+    pub default_banner_image: Option<String>,
+    pub default_banner_tint: Option<String>,
+    pub storage_node: Option<String>,
+}
+
+/// Minehut sends timestamps as milliseconds since the unix epoch.
+pub(crate) fn timestamp(millis: Option<f64>) -> Option<DateTime<Utc>> {
+    millis.and_then(|millis| Utc.timestamp_millis_opt(millis as i64).single())
+}
+
+fn count(value: Option<f64>) -> u32 {
+    value.unwrap_or(0.0).max(0.0) as u32
+}
+
+impl ServerByNameResponse {
+    pub fn typed(&self) -> Server {
+        Server {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            name_lower: self.name_lower.clone(),
+            owner: self.owner.clone(),
+            motd: self.motd.clone(),
+            online: self.online.unwrap_or(false),
+            visibility: self.visibility.unwrap_or(false),
+            suspended: self.suspended.unwrap_or(false),
+            proxy: self.proxy.unwrap_or(false),
+            player_count: count(self.player_count),
+            max_players: count(self.max_players),
+            port: self
+                .port
+                .filter(|port| port.fract() == 0.0 && (0.0..=f64::from(u16::MAX)).contains(port))
+                .map(|port| port as u16),
+            backup_slots: count(self.backup_slots),
+            credits_per_day: self.credits_per_day.unwrap_or(0.0),
+            creation: timestamp(self.creation),
+            last_online: timestamp(self.last_online),
+            platform: self.platform.as_deref().map(Platform::from),
+            server_plan: self.server_plan.as_deref().map(ServerPlan::from),
+            active_server_plan: self.active_server_plan.as_deref().map(ServerPlan::from),
+            raw_plan: self.raw_plan.clone(),
+            server_version_type: self.server_version_type.as_deref().map(VersionType::from),
+            categories: self.categories.clone().unwrap_or_default(),
+            inherited_categories: self.inherited_categories.clone().unwrap_or_default(),
+            connected_servers: self.connected_servers.clone().unwrap_or_default(),
+            purchased_icons: self.purchased_icons.clone().unwrap_or_default(),
+            default_banner_image: self.default_banner_image.clone(),
+            default_banner_tint: self.default_banner_tint.clone(),
+            storage_node: self.storage_node.clone(),
+        }
+    }
+}
+
+impl From<ServerByNameResponse> for Server {
+    fn from(response: ServerByNameResponse) -> Self {
+        response.typed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn typed(fields: serde_json::Value) -> Server {
+        let mut server = json!({ "_id": "5f1a2b3c4d5e6f7a8b9c0d1e" });
+        server
+            .as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        serde_json::from_value::<ServerByNameResponse>(server)
+            .unwrap()
+            .typed()
+    }
+
+    #[test]
+    fn missing_values_fall_back_to_defaults() {
+        let server = typed(json!({}));
+        assert!(!server.online && !server.visibility && !server.suspended && !server.proxy);
+        assert_eq!((server.player_count, server.max_players), (0, 0));
+        assert_eq!(server.port, None);
+        assert_eq!(server.creation, None);
+        assert_eq!(server.platform, None);
+        assert!(server.categories.is_empty());
+    }
+
+    #[test]
+    fn millisecond_timestamps_become_dates() {
+        let server = typed(json!({ "creation": 1_650_000_000_123.0, "last_online": 0 }));
+        assert_eq!(
+            server.creation,
+            Some(
+                Utc.with_ymd_and_hms(2022, 4, 15, 5, 20, 0).unwrap()
+                    + chrono::Duration::milliseconds(123)
+            )
+        );
+        assert_eq!(server.last_online, Some(DateTime::<Utc>::UNIX_EPOCH));
+        assert_eq!(typed(json!({ "creation": 1e300 })).creation, None);
+    }
+
+    #[test]
+    fn known_values_are_matched_ignoring_case() {
+        let server = typed(json!({
+            "platform": "JAVA",
+            "server_plan": "mh20",
+            "activeServerPlan": "MHUNLIMITED",
+            "server_version_type": "Paper",
+        }));
+        assert_eq!(server.platform, Some(Platform::Java));
+        assert_eq!(server.server_plan, Some(ServerPlan::Mh20));
+        assert_eq!(server.active_server_plan, Some(ServerPlan::MhUnlimited));
+        assert_eq!(server.server_version_type, Some(VersionType::Paper));
+    }
+
+    #[test]
+    fn unknown_values_are_kept_as_other() {
+        let server = typed(json!({
+            "platform": "console",
+            "server_plan": "MH100",
+            "server_version_type": "Purpur",
+        }));
+        assert_eq!(
+            server.platform,
+            Some(Platform::Other("console".to_string()))
+        );
+        assert_eq!(
+            server.server_plan,
+            Some(ServerPlan::Other("MH100".to_string()))
+        );
+        assert_eq!(
+            server.server_version_type,
+            Some(VersionType::Other("Purpur".to_string()))
+        );
+        assert_eq!(server.server_plan.unwrap().to_string(), "MH100");
+    }
+
+    #[test]
+    fn ports_outside_u16_or_fractional_are_dropped() {
+        assert_eq!(typed(json!({ "port": 25565 })).port, Some(25565));
+        assert_eq!(typed(json!({ "port": 0 })).port, Some(0));
+        assert_eq!(typed(json!({ "port": 65535 })).port, Some(65535));
+        assert_eq!(typed(json!({ "port": 65536 })).port, None);
+        assert_eq!(typed(json!({ "port": -1 })).port, None);
+        assert_eq!(typed(json!({ "port": 25565.5 })).port, None);
+    }
+
+    #[test]
+    fn counts_are_truncated_and_clamped() {
+        let server = typed(json!({
+            "playerCount": 12.9,
+            "maxPlayers": -5,
+            "backup_slots": 1e12,
+        }));
+        assert_eq!(server.player_count, 12);
+        assert_eq!(server.max_players, 0);
+        assert_eq!(server.backup_slots, u32::MAX);
+    }
+}
@@ -0,0 +1,238 @@
+//! The on-disk format for archiving API responses.
+//!
+//! A snapshot file is JSON Lines: one record per line, each record a JSON object with these
+//! fields:
+//!
+//! | field       | type   | meaning                                                      |
+//! |-------------|--------|--------------------------------------------------------------|
+//! | `version`   | number | The format version, currently [`SNAPSHOT_VERSION`].          |
+//! | `timestamp` | string | When the payload was fetched, RFC 3339 in UTC.               |
+//! | `endpoint`  | string | The endpoint the payload came from, ex: `/server/{name}`.    |
+//! | `payload`   | any    | The response as the models in this crate serialize it.       |
+//!
+//! ```text
+//! {"version":1,"timestamp":"2022-06-01T12:00:00Z","endpoint":"/network/simple_stats","payload":{"player_count":1200,...}}
+//! ```
+//!
+//! New fields may be added to a record without bumping the version; readers ignore fields they
+//! don't know. Removing or changing the meaning of a field bumps the version, and readers refuse
+//! records from a newer version than they understand.
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{BufRead, Write};
+
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SnapshotError {
+    #[error("Failed to read or write snapshot data.")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to encode snapshot record.")]
+    Encode(#[source] serde_json::Error),
+    #[error("Failed to decode snapshot record on line {line}.")]
+    Decode {
+        line: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Snapshot record on line {line} is version {version}, newer than {SNAPSHOT_VERSION}.")]
+    UnsupportedVersion { line: usize, version: u32 },
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct SnapshotRecord<T = serde_json::Value> {
+    pub version: u32,
+    pub timestamp: DateTime<Utc>,
+    pub endpoint: String,
+    pub payload: T,
+}
+
+impl<T> SnapshotRecord<T> {
+    /// A record of `payload` fetched from `endpoint` just now.
+    pub fn new<S: Into<String>>(endpoint: S, payload: T) -> Self {
+        Self::at(Utc::now(), endpoint, payload)
+    }
+
+    pub fn at<S: Into<String>>(timestamp: DateTime<Utc>, endpoint: S, payload: T) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            timestamp,
+            endpoint: endpoint.into(),
+            payload,
+        }
+    }
+}
+
+impl SnapshotRecord {
+    /// Decodes the payload as one of the model types.
+    pub fn payload_as<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        T::deserialize(&self.payload)
+    }
+}
+
+pub struct SnapshotWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn write<T: Serialize>(&mut self, record: &SnapshotRecord<T>) -> Result<(), SnapshotError> {
+        serde_json::to_writer(&mut self.writer, record).map_err(SnapshotError::Encode)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Writes `payload` as fetched from `endpoint` just now.
+    pub fn record<S: Into<String>, T: Serialize>(
+        &mut self,
+        endpoint: S,
+        payload: &T,
+    ) -> Result<(), SnapshotError> {
+        self.write(&SnapshotRecord::new(endpoint, payload))
+    }
+
+    pub fn flush(&mut self) -> Result<(), SnapshotError> {
+        Ok(self.writer.flush()?)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads records back in the order they were written, skipping blank lines.
+pub struct SnapshotReader<R: BufRead> {
+    lines: std::io::Lines<R>,
+    line: usize,
+}
+
+impl<R: BufRead> SnapshotReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for SnapshotReader<R> {
+    type Item = Result<SnapshotRecord, SnapshotError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line += 1;
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err.into())),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record = serde_json::from_str::<SnapshotRecord>(&line).map_err(|source| {
+                SnapshotError::Decode {
+                    line: self.line,
+                    source,
+                }
+            });
+            return Some(record.and_then(|record| {
+                if record.version > SNAPSHOT_VERSION {
+                    Err(SnapshotError::UnsupportedVersion {
+                        line: self.line,
+                        version: record.version,
+                    })
+                } else {
+                    Ok(record)
+                }
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::NetworkSimpleStatsResponse;
+    use std::io::Cursor;
+
+    fn stats() -> NetworkSimpleStatsResponse {
+        serde_json::from_value(serde_json::json!({
+            "player_count": 1200,
+            "server_count": 340,
+            "server_max": 500,
+            "ram_count": 1024,
+            "ram_max": 4096,
+            "motd": "kept as an unknown field",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let timestamp = "2022-06-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut writer = SnapshotWriter::new(Vec::new());
+        writer
+            .write(&SnapshotRecord::at(
+                timestamp,
+                "/network/simple_stats",
+                stats(),
+            ))
+            .unwrap();
+        writer.record("/network/simple_stats", &stats()).unwrap();
+        let written = writer.into_inner();
+
+        let records = SnapshotReader::new(Cursor::new(written))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].version, SNAPSHOT_VERSION);
+        assert_eq!(records[0].timestamp, timestamp);
+        assert_eq!(records[0].endpoint, "/network/simple_stats");
+
+        let payload = records[0]
+            .payload_as::<NetworkSimpleStatsResponse>()
+            .unwrap();
+        assert_eq!(payload.player_count, 1200);
+        assert_eq!(payload.ram_max, 4096);
+        assert_eq!(
+            payload.extra.get("motd"),
+            Some(&serde_json::json!("kept as an unknown field"))
+        );
+        assert_eq!(records[1].payload, records[0].payload);
+    }
+
+    #[test]
+    fn blank_lines_are_skipped_and_lines_counted() {
+        let input = "\n{\"version\":1,\"timestamp\":\"2022-06-01T12:00:00Z\",\"endpoint\":\"/a\",\"payload\":1}\n\nnot json\n";
+        let mut reader = SnapshotReader::new(Cursor::new(input));
+        assert_eq!(reader.next().unwrap().unwrap().endpoint, "/a");
+        assert!(matches!(
+            reader.next(),
+            Some(Err(SnapshotError::Decode { line: 4, .. }))
+        ));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let input = format!(
+            "{{\"version\":1,\"timestamp\":\"2022-06-01T12:00:00Z\",\"endpoint\":\"/a\",\"payload\":null}}\n\
+             {{\"version\":{},\"timestamp\":\"2022-06-01T12:00:00Z\",\"endpoint\":\"/a\",\"payload\":null}}\n",
+            SNAPSHOT_VERSION + 1
+        );
+        let mut reader = SnapshotReader::new(Cursor::new(input));
+        assert!(reader.next().unwrap().is_ok());
+        match reader.next() {
+            Some(Err(SnapshotError::UnsupportedVersion { line, version })) => {
+                assert_eq!(line, 2);
+                assert_eq!(version, SNAPSHOT_VERSION + 1);
+            }
+            other => panic!("expected an unsupported version, got {other:?}"),
+        }
+    }
+}
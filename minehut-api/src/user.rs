@@ -0,0 +1,150 @@
+use crate::client::{default_client, MinehutClient};
+use crate::error::{MinehutError, Result};
+use crate::rest::{ServerByNameResponse, ServerRef};
+use std::collections::HashMap;
+
+/// A Minehut account, public lookups leave out the private fields like `email`.
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct UserProfile {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub username: Option<String>,
+    pub rank: Option<String>,
+    /// The ids of every server the user owns.
+    pub servers: Option<Vec<String>>,
+    pub cosmetics: Option<UserCosmetics>,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+    pub credits: Option<f64>,
+    pub max_servers: Option<f64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl UserProfile {
+    /// The username if Minehut shared one, otherwise the account id.
+    pub fn display_name(&self) -> &str {
+        self.username.as_deref().unwrap_or(&self.id)
+    }
+
+    pub fn server_refs(&self) -> Vec<ServerRef> {
+        self.servers
+            .iter()
+            .flatten()
+            .map(|id| ServerRef::Id(id.clone()))
+            .collect()
+    }
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+pub struct UserCosmetics {
+    pub purchased: Option<Vec<String>>,
+    /// The cosmetic in use for each slot, ex: `hat`.
+    pub selected: Option<HashMap<String, String>>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+struct WrappedProfile {
+    profile: Option<UserProfile>,
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+struct WrappedUser {
+    user: Option<UserProfile>,
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(serde_derive::Deserialize, serde_derive::Serialize, Clone, Debug)]
+struct WrappedServers {
+    servers: Vec<ServerByNameResponse>,
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+impl MinehutClient {
+    /// The public profile of any user, ex: a server's `owner`.
+    pub async fn get_user_profile<S: Into<String> + std::fmt::Display>(
+        &self,
+        id: S,
+    ) -> Result<UserProfile> {
+        let call = self
+            .call(format!("/users/{id}/profile"))
+            .endpoint("/users/{id}/profile");
+        let profile = call
+            .get::<WrappedProfile>()
+            .await?
+            .profile
+            .ok_or_else(|| MinehutError::NotFound {
+                path: call.path().to_string(),
+            })?;
+        self.observe_extra("/users/{id}/profile", "profile.", &profile.extra);
+        Ok(profile)
+    }
+
+    /// The public profile of whoever owns `server`.
+    pub async fn get_server_owner(&self, server: &ServerByNameResponse) -> Result<UserProfile> {
+        match &server.owner {
+            Some(owner) => self.get_user_profile(owner).await,
+            None => Err(MinehutError::NotFound {
+                path: format!("/server/{}/owner", server.id),
+            }),
+        }
+    }
+
+    /// The full profile of user `id`, which has to be the session's own account.
+    pub async fn get_user<S: Into<String> + std::fmt::Display>(
+        &self,
+        id: S,
+    ) -> Result<UserProfile> {
+        let call = self.call(format!("/user/{id}")).endpoint("/user/{id}");
+        let user = call
+            .get::<WrappedUser>()
+            .await?
+            .user
+            .ok_or_else(|| MinehutError::NotFound {
+                path: call.path().to_string(),
+            })?;
+        self.observe_extra("/user/{id}", "user.", &user.extra);
+        Ok(user)
+    }
+
+    /// The full profile of the account the client's session is logged in as.
+    pub async fn get_current_user(&self) -> Result<UserProfile> {
+        let user_id = self.current_user_id().await?;
+        self.get_user(user_id).await
+    }
+
+    /// Every server owned by user `id` in full, which has to be the session's own account.
+    pub async fn get_owned_servers<S: Into<String> + std::fmt::Display>(
+        &self,
+        id: S,
+    ) -> Result<Vec<ServerByNameResponse>> {
+        Ok(self
+            .call(format!("/user/{id}/servers"))
+            .endpoint("/user/{id}/servers")
+            .get::<WrappedServers>()
+            .await?
+            .servers)
+    }
+
+    async fn current_user_id(&self) -> Result<String> {
+        let token = match self.session() {
+            Some(session) => session.current(self).await?,
+            None => None,
+        };
+        token
+            .and_then(|token| token.user_id)
+            .ok_or_else(|| MinehutError::Unauthorized {
+                path: String::from("/user/{id}"),
+            })
+    }
+}
+
+pub async fn get_user_profile<S: Into<String> + std::fmt::Display>(id: S) -> Result<UserProfile> {
+    default_client().get_user_profile(id).await
+}
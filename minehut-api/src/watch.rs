@@ -0,0 +1,125 @@
+//! Polling Minehut's API as a [`Stream`].
+//!
+//! Every watch fetches once straight away, then again `interval` after each fetch finishes.
+//! Failed fetches are yielded as errors without ending the stream.
+
+use crate::client::{default_client, MinehutClient};
+use crate::error::MinehutError;
+use crate::rest::{NetworkSimpleStatsResponse, ServerByNameResponse};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream};
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug)]
+pub struct WatchEvent<T> {
+    pub value: T,
+    /// How long the fetch took.
+    pub latency: Duration,
+    /// When the fetch started.
+    pub fetched_at: DateTime<Utc>,
+}
+
+type Fingerprint<T> = fn(&T) -> Option<serde_json::Value>;
+
+/// A stream of [`WatchEvent`]s, see [`watch`].
+#[must_use = "streams do nothing unless polled"]
+pub struct Watch<T, E = MinehutError> {
+    inner: Pin<Box<dyn Stream<Item = Result<WatchEvent<T>, E>> + Send>>,
+    fingerprint: Option<Fingerprint<T>>,
+    last: Option<serde_json::Value>,
+}
+
+impl<T: Serialize, E> Watch<T, E> {
+    /// Only yields values which differ from the last one yielded, errors are always yielded.
+    pub fn changes_only(mut self) -> Self {
+        self.fingerprint = Some(|value| serde_json::to_value(value).ok());
+        self
+    }
+}
+
+impl<T, E> Stream for Watch<T, E> {
+    type Item = Result<WatchEvent<T>, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            let event = match ready!(this.inner.as_mut().poll_next(cx)) {
+                Some(Ok(event)) => event,
+                other => return Poll::Ready(other),
+            };
+            if let Some(fingerprint) = this.fingerprint {
+                let current = fingerprint(&event.value);
+                if current.is_some() && current == this.last {
+                    continue;
+                }
+                this.last = current;
+            }
+            return Poll::Ready(Some(Ok(event)));
+        }
+    }
+}
+
+/// Calls `fetch` every `interval` forever.
+pub fn watch<T, E, F, Fut>(interval: Duration, fetch: F) -> Watch<T, E>
+where
+    T: Send + 'static,
+    E: Send + 'static,
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T, E>> + Send,
+{
+    let inner = stream::unfold((fetch, true), move |(mut fetch, first)| async move {
+        if !first {
+            tokio::time::sleep(interval).await;
+        }
+        let fetched_at = Utc::now();
+        let started = Instant::now();
+        let event = fetch().await.map(|value| WatchEvent {
+            value,
+            latency: started.elapsed(),
+            fetched_at,
+        });
+        Some((event, (fetch, false)))
+    });
+
+    Watch {
+        inner: Box::pin(inner),
+        fingerprint: None,
+        last: None,
+    }
+}
+
+impl MinehutClient {
+    pub fn watch_simple_stats(&self, interval: Duration) -> Watch<NetworkSimpleStatsResponse> {
+        let client = self.clone();
+        watch(interval, move || {
+            let client = client.clone();
+            async move { client.get_simple_stats().await }
+        })
+    }
+
+    pub fn watch_server<S: Into<String>>(
+        &self,
+        name: S,
+        interval: Duration,
+    ) -> Watch<ServerByNameResponse> {
+        let client = self.clone();
+        let name = name.into();
+        watch(interval, move || {
+            let client = client.clone();
+            let name = name.clone();
+            async move { client.get_server_by_name(name).await }
+        })
+    }
+}
+
+pub fn watch_simple_stats(interval: Duration) -> Watch<NetworkSimpleStatsResponse> {
+    default_client().watch_simple_stats(interval)
+}
+
+pub fn watch_server<S: Into<String>>(name: S, interval: Duration) -> Watch<ServerByNameResponse> {
+    default_client().watch_server(name, interval)
+}
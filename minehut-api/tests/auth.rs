@@ -0,0 +1,60 @@
+use minehut_api::cassette::Cassette;
+use minehut_api::prelude::*;
+use minehut_mock::MockConfig;
+use std::net::SocketAddr;
+
+async fn mock() -> SocketAddr {
+    minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), MockConfig::default())
+        .await
+        .unwrap()
+}
+
+fn logged_in(addr: SocketAddr, password: &str) -> MinehutClientBuilder {
+    MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .session(Session::with_credentials(Credentials::new(
+            "owner@mock.minehut",
+            password,
+        )))
+}
+
+#[tokio::test]
+async fn logs_in_and_fetches_the_current_user() {
+    let addr = mock().await;
+    let client = logged_in(addr, "mock-password").build().unwrap();
+
+    let user = client.get_current_user().await.unwrap();
+    assert_eq!(user.id, "6a7b8c9d0e1f2a3b4c5d6e7f");
+    assert_eq!(user.username.as_deref(), Some("MockOwner"));
+    assert_eq!(user.email.as_deref(), Some("owner@mock.minehut"));
+}
+
+#[tokio::test]
+async fn wrong_passwords_are_unauthorized() {
+    let addr = mock().await;
+    let client = logged_in(addr, "not-the-password").build().unwrap();
+
+    assert!(matches!(
+        client.get_current_user().await,
+        Err(MinehutError::Unauthorized { .. })
+    ));
+}
+
+#[tokio::test]
+async fn recordings_leave_out_the_session() {
+    let path = std::env::temp_dir().join(format!("minehut-api-{}-login.jsonl", std::process::id()));
+    let addr = mock().await;
+    let client = logged_in(addr, "mock-password")
+        .cassette(Cassette::record(&path).unwrap())
+        .build()
+        .unwrap();
+    client.get_current_user().await.unwrap();
+    drop(client);
+
+    let recording = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(recording.contains("/users/login"));
+    assert!(recording.contains("MockOwner"));
+    assert!(!recording.contains("mock-token-"));
+    assert!(!recording.contains("mock-session-"));
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "blocking")]
+
+use futures::StreamExt;
+use minehut_api::blocking::MinehutClient;
+use minehut_api::prelude::{Credentials, MinehutError, ServerListFilter, Session};
+use minehut_mock::MockConfig;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// Serves the mock from its own thread, since blocking calls can't run inside a runtime.
+fn mock() -> SocketAddr {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async move {
+            let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), MockConfig::default())
+                .await
+                .unwrap();
+            sender.send(addr).unwrap();
+            std::future::pending::<()>().await
+        })
+    });
+    receiver.recv().unwrap()
+}
+
+fn client(addr: SocketAddr) -> MinehutClient {
+    MinehutClient::new(
+        minehut_api::client::MinehutClient::builder()
+            .api(format!("http://{addr}"))
+            .build()
+            .unwrap(),
+    )
+}
+
+#[test]
+fn calls_block_until_answered() {
+    let client = client(mock());
+
+    assert_eq!(client.get_simple_stats().unwrap().player_count, 1234);
+    let server = client.get_server_by_name("MockHub").unwrap();
+    assert_eq!(server.name.as_deref(), Some("MockHub"));
+    assert!(matches!(
+        client.get_server_by_name("NoSuchServer"),
+        Err(MinehutError::NotFound { .. })
+    ));
+
+    let raw = client
+        .call("/network/simple_stats")
+        .get::<serde_json::Value>()
+        .unwrap();
+    assert_eq!(raw["player_count"], 1234);
+}
+
+#[test]
+fn streams_are_iterated() {
+    let client = client(mock());
+
+    let listed = client
+        .list_servers(ServerListFilter::default())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(listed.len(), 2);
+
+    let watched = client
+        .iter(
+            client
+                .inner()
+                .watch_server("MockHub", Duration::from_millis(10))
+                .take(2),
+        )
+        .map(|event| event.unwrap().value.name)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        watched,
+        [Some("MockHub".to_string()), Some("MockHub".to_string())]
+    );
+}
+
+#[test]
+fn owned_servers_are_managed() {
+    let addr = mock();
+    let client = MinehutClient::new(
+        minehut_api::client::MinehutClient::builder()
+            .api(format!("http://{addr}"))
+            .session(Session::with_credentials(Credentials::new(
+                "owner@mock.minehut",
+                "mock-password",
+            )))
+            .build()
+            .unwrap(),
+    );
+
+    let change = client
+        .owned_server("5f1a2b3c4d5e6f7a8b9c0d1e")
+        .change(|server| server.set_motd("Back soon!"))
+        .unwrap();
+    assert_eq!(change.after.motd.as_deref(), Some("Back soon!"));
+    assert_eq!(change.differences().len(), 1);
+}
+
+#[test]
+fn clients_can_be_dropped_inside_a_runtime() {
+    let client = client(mock());
+    client.get_simple_stats().unwrap();
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async move { drop(client) });
+}
@@ -0,0 +1,173 @@
+use minehut_api::prelude::*;
+use minehut_mock::scenario::Rule;
+use minehut_mock::MockConfig;
+use std::time::Duration;
+
+/// A mock which answers `/network/simple_stats` slowly, and only once.
+async fn single_answer_client(ttl: Duration) -> MinehutClient {
+    let scenario = serde_json::json!([
+        { "endpoint": "/network/simple_stats", "fault": { "kind": "latency", "millis": 200 } },
+        {
+            "endpoint": "/network/simple_stats",
+            "after": 1,
+            "fault": { "kind": "status", "status": 500, "body": "Already answered" }
+        }
+    ]);
+    let config = MockConfig {
+        scenario: serde_json::from_value::<Vec<Rule>>(scenario).unwrap(),
+        ..MockConfig::default()
+    };
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), config)
+        .await
+        .unwrap();
+    MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .cache(CacheConfig::new(ttl))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn concurrent_identical_calls_share_one_request() {
+    let client = single_answer_client(Duration::from_secs(60)).await;
+
+    let calls = (0..8).map(|_| {
+        let client = client.clone();
+        tokio::spawn(async move { client.get_simple_stats().await })
+    });
+    for call in futures::future::join_all(calls).await {
+        assert_eq!(call.unwrap().unwrap().player_count, 1234);
+    }
+
+    let stats = client.cache().unwrap().stats();
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.hits + stats.coalesced, 7);
+}
+
+#[tokio::test]
+async fn expired_entries_are_fetched_again() {
+    let client = single_answer_client(Duration::from_millis(300)).await;
+
+    assert!(client.get_simple_stats().await.is_ok());
+    assert!(client.get_simple_stats().await.is_ok());
+    assert_eq!(client.cache().unwrap().stats().hits, 1);
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    // the mock only answers once, so reaching it again means the entry expired
+    assert!(matches!(
+        client.get_simple_stats().await,
+        Err(MinehutError::Status { status: 500, .. })
+    ));
+    assert_eq!(client.cache().unwrap().stats().misses, 2);
+}
+
+#[tokio::test]
+async fn writes_invalidate_cached_reads_of_the_resource() {
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), MockConfig::default())
+        .await
+        .unwrap();
+    let client = MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .cache(CacheConfig::new(Duration::from_secs(60)))
+        .session(Session::with_credentials(Credentials::new(
+            "owner@mock.minehut",
+            "mock-password",
+        )))
+        .build()
+        .unwrap();
+    // shares the cache, but its calls skip the session so they're cached
+    let public = client.anonymous();
+
+    let before = public.get_server_by_name("MockSurvival").await.unwrap();
+    let id = before.id.clone();
+    assert!(public.get_server_by_name("MockSurvival").await.is_ok());
+    assert_eq!(client.cache().unwrap().stats().hits, 1);
+
+    client
+        .owned_server(id)
+        .set_motd("Back soon!")
+        .await
+        .unwrap();
+
+    let after = public.get_server_by_name("MockSurvival").await.unwrap();
+    assert_eq!(after.motd.as_deref(), Some("Back soon!"));
+    assert_eq!(client.cache().unwrap().stats().hits, 1);
+}
+
+#[tokio::test]
+async fn zero_ttls_only_cache_overridden_endpoints() {
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), MockConfig::default())
+        .await
+        .unwrap();
+    let client = MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .cache(CacheConfig::new(Duration::ZERO).ttl("/plans", Duration::from_secs(60 * 60)))
+        .build()
+        .unwrap();
+
+    for _ in 0..2 {
+        assert!(!client.get_plans().await.unwrap().is_empty());
+        client.get_simple_stats().await.unwrap();
+    }
+
+    let stats = client.cache().unwrap().stats();
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.hits, 1);
+}
+
+#[tokio::test]
+async fn stale_entries_are_revalidated() {
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), MockConfig::default())
+        .await
+        .unwrap();
+    let client = MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .cache(CacheConfig::new(Duration::from_millis(200)))
+        .build()
+        .unwrap();
+
+    let before = client.get_simple_stats().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let after = client.get_simple_stats().await.unwrap();
+    assert_eq!(after.player_count, before.player_count);
+    let stats = client.cache().unwrap().stats();
+    assert_eq!((stats.misses, stats.revalidated), (1, 1));
+
+    // the revalidated entry is fresh again
+    client.get_simple_stats().await.unwrap();
+    assert_eq!(client.cache().unwrap().stats().hits, 1);
+}
+
+#[tokio::test]
+async fn changed_entries_are_refreshed_when_revalidated() {
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), MockConfig::default())
+        .await
+        .unwrap();
+    let client = MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .cache(CacheConfig::new(Duration::from_millis(200)))
+        .build()
+        .unwrap();
+    // a separate client, so its write doesn't invalidate the cache under test
+    let owner = MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .session(Session::with_credentials(Credentials::new(
+            "owner@mock.minehut",
+            "mock-password",
+        )))
+        .build()
+        .unwrap();
+
+    let before = client.get_server_by_name("MockSurvival").await.unwrap();
+    owner
+        .owned_server(before.id.clone())
+        .set_motd("Back soon!")
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let after = client.get_server_by_name("MockSurvival").await.unwrap();
+    assert_eq!(after.motd.as_deref(), Some("Back soon!"));
+    let stats = client.cache().unwrap().stats();
+    assert_eq!((stats.misses, stats.revalidated), (2, 0));
+}
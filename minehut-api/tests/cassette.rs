@@ -0,0 +1,95 @@
+use minehut_api::cassette::Cassette;
+use minehut_api::prelude::*;
+use minehut_mock::MockConfig;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+fn cassette_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("minehut-api-{}-{name}.jsonl", std::process::id()))
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+}
+
+fn client(addr: SocketAddr, cassette: Cassette) -> MinehutClient {
+    MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .cassette(cassette)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn replays_a_recording_without_the_mock() {
+    let path = cassette_path("round-trip");
+
+    // the mock only lives as long as this runtime
+    let recording = runtime();
+    let (addr, recorded_stats, recorded_server) = recording.block_on(async {
+        let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), MockConfig::default())
+            .await
+            .unwrap();
+        let client = client(addr, Cassette::record(&path).unwrap());
+        let stats = client.get_simple_stats().await.unwrap();
+        let server = client.get_server_by_name("MockHub").await.unwrap();
+        (addr, stats, server)
+    });
+    drop(recording);
+
+    runtime().block_on(async {
+        let offline = MinehutClient::builder()
+            .api(format!("http://{addr}"))
+            .build()
+            .unwrap();
+        assert!(matches!(
+            offline.get_simple_stats().await,
+            Err(MinehutError::Transport { .. })
+        ));
+
+        let client = client(addr, Cassette::replay(&path).unwrap());
+        let stats = client.get_simple_stats().await.unwrap();
+        assert_eq!(stats.player_count, recorded_stats.player_count);
+        assert_eq!(stats.server_count, recorded_stats.server_count);
+        let server = client.get_server_by_name("MockHub").await.unwrap();
+        assert_eq!(server.id, recorded_server.id);
+        assert_eq!(server.motd, recorded_server.motd);
+
+        // the last recording keeps being served
+        assert!(client.get_simple_stats().await.is_ok());
+    });
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn missing_interactions_are_not_recorded_errors() {
+    let path = cassette_path("missing");
+
+    let recording = runtime();
+    let addr = recording.block_on(async {
+        let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), MockConfig::default())
+            .await
+            .unwrap();
+        let client = client(addr, Cassette::record(&path).unwrap());
+        client.get_simple_stats().await.unwrap();
+        addr
+    });
+    drop(recording);
+
+    runtime().block_on(async {
+        let client = client(addr, Cassette::replay(&path).unwrap());
+        match client.get_server_by_name("MockHub").await {
+            Err(MinehutError::NotRecorded { method, path }) => {
+                assert_eq!(method, "GET");
+                assert_eq!(path, "/server/MockHub?byName=true");
+            }
+            other => panic!("expected NotRecorded, got {other:?}"),
+        }
+    });
+
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,88 @@
+use minehut_api::prelude::*;
+use minehut_mock::{Fixtures, MockConfig};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+/// A client against `fixtures` which collects every drift report.
+async fn client(fixtures: Fixtures) -> (MinehutClient, Arc<Mutex<Vec<DriftReport>>>) {
+    let addr = minehut_mock::spawn(
+        "127.0.0.1:0".parse().unwrap(),
+        MockConfig {
+            fixtures,
+            ..MockConfig::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let callback_reports = Arc::clone(&reports);
+    let client = MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .drift_mode(DriftMode::Callback(Arc::new(move |report| {
+            callback_reports.lock().unwrap().push(report.clone());
+        })))
+        .build()
+        .unwrap();
+    (client, reports)
+}
+
+#[tokio::test]
+async fn unknown_fields_are_captured_and_reported() {
+    let mut fixtures = Fixtures::default();
+    fixtures.servers[1]["bannerAnimation"] = json!("sparkle");
+    let (client, reports) = client(fixtures).await;
+
+    let known = client.get_server_by_name("MockSurvival").await.unwrap();
+    assert!(known.extra.is_empty());
+    assert!(reports.lock().unwrap().is_empty());
+
+    let drifted = client.get_server_by_name("MockHub").await.unwrap();
+    assert_eq!(
+        drifted.extra.get("bannerAnimation"),
+        Some(&json!("sparkle"))
+    );
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].endpoint, "/server/{name}");
+    assert!(reports[0].changes.contains(&FieldDrift::Added {
+        field: "server.bannerAnimation".to_string(),
+        kind: FieldKind::String,
+    }));
+}
+
+#[tokio::test]
+async fn unknown_fields_in_the_first_response_are_reported() {
+    let mut fixtures = Fixtures::default();
+    fixtures.servers[0]["bannerAnimation"] = json!("sparkle");
+    let (client, reports) = client(fixtures).await;
+
+    client.get_server_by_name("MockSurvival").await.unwrap();
+    client.get_server_by_name("MockSurvival").await.unwrap();
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(
+        reports[0].changes,
+        vec![FieldDrift::Added {
+            field: "server.bannerAnimation".to_string(),
+            kind: FieldKind::String,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn servers_with_different_optional_fields_do_not_drift() {
+    let mut fixtures = Fixtures::default();
+    fixtures.servers[1]["bannerAnimation"] = json!("sparkle");
+    let (client, reports) = client(fixtures).await;
+
+    for _ in 0..10 {
+        client.get_server_by_name("MockSurvival").await.unwrap();
+        client.get_server_by_name("MockHub").await.unwrap();
+    }
+
+    // only the first sight of the unknown field
+    assert_eq!(reports.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,76 @@
+use futures::TryStreamExt;
+use minehut_api::prelude::*;
+use minehut_mock::{Fixtures, MockConfig};
+use std::sync::Arc;
+
+/// A mock listing `count` online servers.
+async fn client_listing(count: usize, metrics: Arc<ApiMetrics>) -> MinehutClient {
+    let mut fixtures = Fixtures::default();
+    let template = fixtures.servers[0].clone();
+    fixtures.servers = (0..count)
+        .map(|index| {
+            let mut server = template.clone();
+            server["_id"] = format!("{index:024x}").into();
+            server["name"] = format!("Listed{index}").into();
+            server["online"] = true.into();
+            server
+        })
+        .collect();
+    let config = MockConfig {
+        fixtures,
+        ..MockConfig::default()
+    };
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), config)
+        .await
+        .unwrap();
+    MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .metrics(metrics)
+        .build()
+        .unwrap()
+}
+
+async fn list_all(count: usize) -> (Vec<ServerListing>, u64) {
+    let metrics = Arc::new(ApiMetrics::new());
+    let client = client_listing(count, metrics.clone()).await;
+    let servers = client
+        .list_servers(ServerListFilter::default())
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    let pages = metrics
+        .endpoint("/servers")
+        .map_or(0, |metrics| metrics.calls);
+    (servers, pages)
+}
+
+#[tokio::test]
+async fn pages_through_every_server() {
+    let (servers, pages) = list_all(250).await;
+    assert_eq!(servers.len(), 250);
+    assert_eq!(pages, 3);
+
+    let names = servers.iter().map(|server| server.name.clone().unwrap());
+    assert!(names
+        .clone()
+        .eq((0..250).map(|index| format!("Listed{index}"))));
+}
+
+#[tokio::test]
+async fn a_full_last_page_stops_at_the_total() {
+    let (servers, pages) = list_all(200).await;
+    assert_eq!(servers.len(), 200);
+    assert_eq!(pages, 2);
+}
+
+#[tokio::test]
+async fn filters_apply_across_pages() {
+    let metrics = Arc::new(ApiMetrics::new());
+    let client = client_listing(150, metrics).await;
+    let servers = client
+        .list_servers(ServerListFilter::default().min_players(1_000_000))
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert!(servers.is_empty());
+}
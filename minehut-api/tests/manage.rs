@@ -0,0 +1,155 @@
+use minehut_api::prelude::*;
+use minehut_mock::MockConfig;
+use serde_json::json;
+use std::time::Duration;
+
+const SURVIVAL_ID: &str = "5f1a2b3c4d5e6f7a8b9c0d1e";
+const HUB_ID: &str = "5f1a2b3c4d5e6f7a8b9c0d1f";
+const OFFLINE_ID: &str = "5f1a2b3c4d5e6f7a8b9c0d20";
+
+/// How long the mock takes to start, stop or hibernate a server.
+const TRANSITION: Duration = Duration::from_millis(100);
+
+async fn owner() -> MinehutClient {
+    let addr = minehut_mock::spawn(
+        "127.0.0.1:0".parse().unwrap(),
+        MockConfig {
+            transition_millis: Some(TRANSITION.as_millis() as u64),
+            ..MockConfig::default()
+        },
+    )
+    .await
+    .unwrap();
+    MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .session(Session::with_credentials(Credentials::new(
+            "owner@mock.minehut",
+            "mock-password",
+        )))
+        .build()
+        .unwrap()
+}
+
+async fn settled(server: &OwnedServer) -> ServerStatus {
+    tokio::time::sleep(TRANSITION * 2).await;
+    server.status().await.unwrap()
+}
+
+#[tokio::test]
+async fn servers_start_and_stop() {
+    let client = owner().await;
+    let server = client.owned_server(OFFLINE_ID);
+
+    let status = server.status().await.unwrap();
+    assert_eq!(status.status, Some(ServiceState::Hibernating));
+    assert!(!status.is_online());
+
+    server.start().await.unwrap();
+    let status = server.status().await.unwrap();
+    assert_eq!(status.status, Some(ServiceState::Starting));
+    assert!(status.is_transitioning());
+    let status = settled(&server).await;
+    assert_eq!(status.status, Some(ServiceState::Online));
+    assert!(status.is_online() && !status.is_transitioning());
+
+    server.stop().await.unwrap();
+    assert_eq!(
+        server.status().await.unwrap().status,
+        Some(ServiceState::Stopping)
+    );
+    let status = settled(&server).await;
+    assert_eq!(status.status, Some(ServiceState::Offline));
+    assert_eq!(status.service_online, Some(true));
+}
+
+#[tokio::test]
+async fn hibernating_releases_the_service() {
+    let client = owner().await;
+    let server = client.owned_server(SURVIVAL_ID);
+    assert_eq!(
+        server.status().await.unwrap().status,
+        Some(ServiceState::Online)
+    );
+
+    server.hibernate().await.unwrap();
+    let status = settled(&server).await;
+    assert_eq!(status.status, Some(ServiceState::Hibernating));
+    assert_eq!(status.service_online, Some(false));
+    assert_eq!(status.player_count, Some(0.0));
+    assert_eq!(server.info().await.unwrap().online, Some(false));
+}
+
+#[tokio::test]
+async fn changes_list_every_differing_field() {
+    let client = owner().await;
+    let server = client.owned_server(SURVIVAL_ID);
+
+    let change = server
+        .change(|server| async move {
+            server.set_motd("Back soon!").await?;
+            server.set_visibility(false).await
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        change.before.motd.as_deref(),
+        Some("A friendly survival server")
+    );
+    assert_eq!(change.after.motd.as_deref(), Some("Back soon!"));
+
+    let mut differences = change.differences();
+    differences.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        differences,
+        vec![
+            (
+                "motd".to_string(),
+                json!("A friendly survival server"),
+                json!("Back soon!")
+            ),
+            ("visibility".to_string(), json!(true), json!(false)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn unchanged_servers_have_no_differences() {
+    let client = owner().await;
+    let change = client
+        .owned_server(SURVIVAL_ID)
+        .change(|_| async { Ok(()) })
+        .await
+        .unwrap();
+    assert!(change.differences().is_empty());
+}
+
+#[tokio::test]
+async fn properties_can_be_edited() {
+    let client = owner().await;
+    let server = client.owned_server(SURVIVAL_ID);
+    assert_eq!(server.properties().await.unwrap()["pvp"], json!(true));
+
+    server.set_property("pvp", false).await.unwrap();
+    assert_eq!(server.properties().await.unwrap()["pvp"], json!(false));
+}
+
+#[tokio::test]
+async fn only_owned_servers_can_be_managed() {
+    let client = owner().await;
+    let server = client.owned_server(HUB_ID);
+
+    assert!(matches!(
+        server.stop().await,
+        Err(MinehutError::Status { status: 403, .. })
+    ));
+    assert!(matches!(
+        server.status().await,
+        Err(MinehutError::Status { status: 403, .. })
+    ));
+
+    let anonymous = client.anonymous().owned_server(SURVIVAL_ID);
+    assert!(matches!(
+        anonymous.start().await,
+        Err(MinehutError::Unauthorized { .. })
+    ));
+}
@@ -0,0 +1,69 @@
+use minehut_api::prelude::*;
+use minehut_mock::scenario::Rule;
+use minehut_mock::MockConfig;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+async fn mock(scenario: serde_json::Value) -> MinehutClientBuilder {
+    let config = MockConfig {
+        scenario: serde_json::from_value::<Vec<Rule>>(scenario).unwrap(),
+        ..MockConfig::default()
+    };
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), config)
+        .await
+        .unwrap();
+    MinehutClient::builder().api(format!("http://{addr}"))
+}
+
+#[tokio::test]
+async fn rate_limiting_is_left_out_of_latency() {
+    let metrics = Arc::new(ApiMetrics::new());
+    let client = mock(serde_json::json!([]))
+        .await
+        .rate_limit(5.0, 1)
+        .metrics(metrics.clone())
+        .build()
+        .unwrap();
+
+    let started = Instant::now();
+    for _ in 0..3 {
+        client.get_simple_stats().await.unwrap();
+    }
+    assert!(started.elapsed() >= Duration::from_millis(350));
+
+    let stats = metrics.endpoint("/network/simple_stats").unwrap();
+    assert_eq!(stats.latency.count(), 3);
+    assert!(
+        stats.latency.max().unwrap() < Duration::from_millis(150),
+        "{:?}",
+        stats.latency.max()
+    );
+}
+
+#[tokio::test]
+async fn logging_in_is_recorded_as_its_own_call() {
+    let metrics = Arc::new(ApiMetrics::new());
+    let client = mock(serde_json::json!([{
+        "endpoint": "/users/login",
+        "fault": { "kind": "latency", "millis": 300 }
+    }]))
+    .await
+    .session(Session::with_credentials(Credentials::new(
+        "owner@mock.minehut",
+        "mock-password",
+    )))
+    .metrics(metrics.clone())
+    .build()
+    .unwrap();
+
+    client.get_current_user().await.unwrap();
+
+    let login = metrics.endpoint("/users/login").unwrap();
+    assert!(login.latency.max().unwrap() >= Duration::from_millis(300));
+    let user = metrics.endpoint("/user/{id}").unwrap();
+    assert!(
+        user.latency.max().unwrap() < Duration::from_millis(300),
+        "{:?}",
+        user.latency.max()
+    );
+}
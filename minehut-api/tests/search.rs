@@ -0,0 +1,34 @@
+use minehut_api::prelude::*;
+use minehut_mock::MockConfig;
+
+async fn client() -> MinehutClient {
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), MockConfig::default())
+        .await
+        .unwrap();
+    MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn searches_online_servers() {
+    let client = client().await;
+
+    let matches = client.search_servers("mock", 10).await.unwrap();
+    let names = matches.iter().map(|m| m.name()).collect::<Vec<_>>();
+    // shorter names cover more of the query, offline servers aren't listed
+    assert_eq!(names, ["MockHub", "MockSurvival"]);
+
+    let matches = client.search_servers("mocksurvivl", 10).await.unwrap();
+    assert_eq!(matches[0].name(), "MockSurvival");
+}
+
+#[tokio::test]
+async fn taken_names_are_unavailable_whether_online_or_not() {
+    let client = client().await;
+
+    assert!(!client.is_name_available("MockSurvival").await.unwrap());
+    assert!(!client.is_name_available("mockoffline").await.unwrap());
+    assert!(client.is_name_available("NotAMockServer").await.unwrap());
+}
@@ -0,0 +1,80 @@
+use minehut_api::prelude::*;
+use minehut_mock::scenario::Rule;
+use minehut_mock::MockConfig;
+use std::time::{Duration, Instant};
+
+const SURVIVAL_ID: &str = "5f1a2b3c4d5e6f7a8b9c0d1e";
+const HUB_ID: &str = "5f1a2b3c4d5e6f7a8b9c0d1f";
+
+async fn client(scenario: serde_json::Value) -> MinehutClient {
+    let config = MockConfig {
+        scenario: serde_json::from_value::<Vec<Rule>>(scenario).unwrap(),
+        ..MockConfig::default()
+    };
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), config)
+        .await
+        .unwrap();
+    MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn looks_up_servers_by_id() {
+    let client = client(serde_json::json!([])).await;
+
+    let server = client.get_server_by_id(SURVIVAL_ID).await.unwrap();
+    assert_eq!(server.id, SURVIVAL_ID);
+    assert_eq!(server.name.as_deref(), Some("MockSurvival"));
+
+    // names aren't ids
+    assert!(matches!(
+        client.get_server_by_id("MockSurvival").await,
+        Err(MinehutError::NotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn batches_keep_their_order_and_isolate_failures() {
+    let client = client(serde_json::json!([])).await;
+
+    let servers = vec![
+        ServerRef::Name("MockHub".to_string()),
+        ServerRef::Id("000000000000000000000000".to_string()),
+        ServerRef::Id(SURVIVAL_ID.to_string()),
+        ServerRef::Name("NotAMockServer".to_string()),
+    ];
+    let results = client.get_servers(servers.clone(), 2).await;
+
+    assert_eq!(
+        results.iter().map(|(server, _)| server).collect::<Vec<_>>(),
+        servers.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(results[0].1.as_ref().unwrap().id, HUB_ID);
+    assert!(matches!(results[1].1, Err(MinehutError::NotFound { .. })));
+    assert_eq!(results[2].1.as_ref().unwrap().id, SURVIVAL_ID);
+    assert!(matches!(results[3].1, Err(MinehutError::NotFound { .. })));
+}
+
+#[tokio::test]
+async fn batches_keep_at_most_concurrency_calls_in_flight() {
+    let client = client(serde_json::json!([
+        { "endpoint": "/server/", "fault": { "kind": "latency", "millis": 200 } }
+    ]))
+    .await;
+    let servers = || (0..6).map(|_| ServerRef::Id(HUB_ID.to_string()));
+
+    let started = Instant::now();
+    let results = client.get_servers(servers(), 2).await;
+    let bounded = started.elapsed();
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+    let started = Instant::now();
+    client.get_servers(servers(), 6).await;
+    let unbounded = started.elapsed();
+
+    // three rounds of two calls against one round of six
+    assert!(bounded >= Duration::from_millis(600), "{bounded:?}");
+    assert!(unbounded < Duration::from_millis(600), "{unbounded:?}");
+}
@@ -0,0 +1,89 @@
+use minehut_api::prelude::*;
+use minehut_mock::MockConfig;
+
+const OWNER_ID: &str = "6a7b8c9d0e1f2a3b4c5d6e7f";
+const HUB_OWNER_ID: &str = "6a7b8c9d0e1f2a3b4c5d6e80";
+
+async fn anonymous() -> MinehutClient {
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), MockConfig::default())
+        .await
+        .unwrap();
+    MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn profiles_leave_out_private_fields() {
+    let client = anonymous().await;
+
+    let profile = client.get_user_profile(OWNER_ID).await.unwrap();
+    assert_eq!(profile.id, OWNER_ID);
+    assert_eq!(profile.display_name(), "MockOwner");
+    assert_eq!(profile.email, None);
+    assert_eq!(
+        profile
+            .server_refs()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["5f1a2b3c4d5e6f7a8b9c0d1e", "5f1a2b3c4d5e6f7a8b9c0d20"]
+    );
+}
+
+#[tokio::test]
+async fn unknown_profiles_are_not_found() {
+    let client = anonymous().await;
+    assert!(matches!(
+        client.get_user_profile("000000000000000000000000").await,
+        Err(MinehutError::NotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn servers_resolve_their_owner() {
+    let client = anonymous().await;
+
+    let hub = client.get_server_by_name("MockHub").await.unwrap();
+    let owner = client.get_server_owner(&hub).await.unwrap();
+    assert_eq!(owner.id, HUB_OWNER_ID);
+    assert_eq!(owner.display_name(), "MockHubOwner");
+
+    let mut orphan = hub;
+    orphan.owner = None;
+    match client.get_server_owner(&orphan).await {
+        Err(MinehutError::NotFound { path }) => {
+            assert_eq!(path, "/server/5f1a2b3c4d5e6f7a8b9c0d1f/owner")
+        }
+        other => panic!("expected no owner, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn owned_servers_need_the_owners_session() {
+    let client = anonymous().await;
+    assert!(matches!(
+        client.get_owned_servers(OWNER_ID).await,
+        Err(MinehutError::Unauthorized { .. })
+    ));
+
+    let owner = client.with_session(Session::with_credentials(Credentials::new(
+        "owner@mock.minehut",
+        "mock-password",
+    )));
+    let mut names = owner
+        .get_owned_servers(OWNER_ID)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|server| server.name)
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["MockOffline", "MockSurvival"]);
+
+    assert!(matches!(
+        owner.get_owned_servers(HUB_OWNER_ID).await,
+        Err(MinehutError::Status { status: 403, .. })
+    ));
+}
@@ -0,0 +1,106 @@
+use chrono::Utc;
+use futures::StreamExt;
+use minehut_api::prelude::*;
+use minehut_mock::scenario::Rule;
+use minehut_mock::MockConfig;
+use std::time::Duration;
+
+const INTERVAL: Duration = Duration::from_millis(20);
+const LATENCY: Duration = Duration::from_millis(50);
+
+/// A mock which answers slowly, and takes MockSurvival offline from the third lookup on.
+async fn client() -> MinehutClient {
+    let scenario = serde_json::json!([
+        { "endpoint": "/server/", "fault": { "kind": "latency", "millis": LATENCY.as_millis() } },
+        {
+            "endpoint": "/server/",
+            "after": 2,
+            "fault": { "kind": "offline", "server": "MockSurvival" }
+        }
+    ]);
+    let config = MockConfig {
+        scenario: serde_json::from_value::<Vec<Rule>>(scenario).unwrap(),
+        ..MockConfig::default()
+    };
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), config)
+        .await
+        .unwrap();
+    MinehutClient::builder()
+        .api(format!("http://{addr}"))
+        .build()
+        .unwrap()
+}
+
+/// Everything `watch` yields within `within`.
+async fn collect<T>(watch: Watch<T>, within: Duration) -> Vec<Result<WatchEvent<T>, MinehutError>> {
+    let mut events = Vec::new();
+    let _ = tokio::time::timeout(
+        within,
+        watch.for_each(|event| {
+            events.push(event);
+            async {}
+        }),
+    )
+    .await;
+    events
+}
+
+#[tokio::test]
+async fn every_fetch_is_yielded() {
+    let client = client().await;
+    let started = Utc::now();
+
+    let events = collect(
+        client.watch_server("MockSurvival", INTERVAL),
+        Duration::from_millis(500),
+    )
+    .await;
+
+    // one fetch every interval plus latency, which is 70ms here
+    assert!((3..=8).contains(&events.len()), "{}", events.len());
+    let mut fetched_at = started;
+    for event in events {
+        let event = event.unwrap();
+        assert!(event.latency >= LATENCY, "{:?}", event.latency);
+        assert!(event.fetched_at >= fetched_at);
+        fetched_at = event.fetched_at;
+    }
+}
+
+#[tokio::test]
+async fn changes_only_skips_repeated_values() {
+    let client = client().await;
+
+    let events = collect(
+        client.watch_server("MockSurvival", INTERVAL).changes_only(),
+        Duration::from_millis(500),
+    )
+    .await
+    .into_iter()
+    .map(Result::unwrap)
+    .collect::<Vec<_>>();
+
+    // the second fetch repeats the first and every fetch after the third repeats it
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].value.online, Some(true));
+    assert_eq!(events[1].value.online, Some(false));
+    // the skipped fetch and the interval lie between the two
+    let between = events[1].fetched_at - events[0].fetched_at;
+    assert!(between.to_std().unwrap() >= (INTERVAL + LATENCY) * 2);
+}
+
+#[tokio::test]
+async fn failed_fetches_are_yielded_without_ending_the_stream() {
+    let client = client().await;
+
+    let events = collect(
+        client.watch_server("NoSuchServer", INTERVAL).changes_only(),
+        Duration::from_millis(300),
+    )
+    .await;
+
+    assert!(events.len() >= 2);
+    assert!(events
+        .iter()
+        .all(|event| matches!(event, Err(MinehutError::NotFound { .. }))));
+}
@@ -0,0 +1,67 @@
+use axum::http::{header, HeaderMap};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const SESSION_ID_HEADER: &str = "x-session-id";
+
+#[derive(Debug)]
+struct Issued {
+    user_id: String,
+    session_id: String,
+    issued_at: Instant,
+}
+
+/// Tokens handed out by `/users/login`, which stop working `ttl` after being issued.
+#[derive(Debug)]
+pub struct Sessions {
+    ttl: Option<Duration>,
+    issued: Mutex<HashMap<String, Issued>>,
+    next: AtomicU64,
+}
+
+impl Sessions {
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            ttl,
+            issued: Mutex::new(HashMap::new()),
+            next: AtomicU64::new(1),
+        }
+    }
+
+    /// Issues a new token and session id for `user_id`.
+    pub fn issue(&self, user_id: &str) -> (String, String) {
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        let token = format!("mock-token-{next}");
+        let session_id = format!("mock-session-{next}");
+        self.issued.lock().unwrap().insert(
+            token.clone(),
+            Issued {
+                user_id: user_id.to_string(),
+                session_id: session_id.clone(),
+                issued_at: Instant::now(),
+            },
+        );
+        (token, session_id)
+    }
+
+    /// The user whose live token and matching session id are in `headers`.
+    pub fn verify(&self, headers: &HeaderMap) -> Option<String> {
+        let token = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+        let token = token.strip_prefix("Bearer ").unwrap_or(token);
+        let session_id = headers
+            .get(SESSION_ID_HEADER)
+            .and_then(|value| value.to_str().ok());
+
+        let issued = self.issued.lock().unwrap();
+        let issued = issued.get(token)?;
+        let expired = self
+            .ttl
+            .is_some_and(|ttl| issued.issued_at.elapsed() >= ttl);
+        if expired || session_id.is_some_and(|session_id| session_id != issued.session_id) {
+            return None;
+        }
+        Some(issued.user_id.clone())
+    }
+}
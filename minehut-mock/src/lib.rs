@@ -0,0 +1,510 @@
+//! A local stand-in for Minehut's API, serving fixtures with scripted faults.
+//!
+//! Point `MINEHUT_URL` at it, ex: `MINEHUT_URL=http://127.0.0.1:8080`.
+//!
+//! Public reads carry an `ETag` and answer a matching `If-None-Match` with 304 Not Modified.
+
+pub mod auth;
+mod manage;
+pub mod scenario;
+
+use auth::Sessions;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use scenario::{Fault, Outcome, Rule, Scenario};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const DEFAULT_FIXTURES: &str = include_str!("../fixtures/default.json");
+
+#[derive(thiserror::Error, Debug)]
+pub enum MockError {
+    #[error("Failed to read the mock configuration.")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to decode the mock configuration.")]
+    Decode(#[from] serde_json::Error),
+}
+
+/// The data the mock serves, shaped exactly like Minehut's responses.
+#[derive(serde_derive::Deserialize, Clone, Debug)]
+pub struct Fixtures {
+    pub simple_stats: Value,
+    /// Servers as `/server/{name}?byName=true` returns them, without the `server` wrapper.
+    #[serde(default)]
+    pub servers: Vec<Value>,
+    /// Accounts which can log in, each with an `email` and `password` alongside its profile.
+    #[serde(default)]
+    pub users: Vec<Value>,
+    #[serde(default)]
+    pub plans: Vec<Value>,
+    #[serde(default)]
+    pub categories: Vec<Value>,
+    #[serde(default)]
+    pub icons: Vec<Value>,
+}
+
+impl Default for Fixtures {
+    fn default() -> Self {
+        serde_json::from_str(DEFAULT_FIXTURES).expect("Default fixtures are invalid.")
+    }
+}
+
+#[derive(serde_derive::Deserialize, Clone, Debug, Default)]
+pub struct MockConfig {
+    #[serde(default)]
+    pub fixtures: Fixtures,
+    #[serde(default)]
+    pub scenario: Vec<Rule>,
+    /// How long issued tokens work for, forever if unset.
+    #[serde(default)]
+    pub token_ttl_secs: Option<u64>,
+    /// How long owned servers take to start, stop or hibernate, 2 seconds if unset.
+    #[serde(default)]
+    pub transition_millis: Option<u64>,
+}
+
+impl MockConfig {
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, MockError> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+}
+
+struct MockState {
+    fixtures: Fixtures,
+    scenario: Scenario,
+    sessions: Sessions,
+    /// The fixture servers, as changed by their owners since.
+    servers: Mutex<Vec<Value>>,
+    transitions: Mutex<HashMap<String, manage::Transition>>,
+    transition: Duration,
+}
+
+impl MockState {
+    fn find_server(&self, key: &str, by_name: bool) -> Option<Value> {
+        self.settle();
+        self.servers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|server| {
+                if by_name {
+                    server["name"]
+                        .as_str()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(key))
+                } else {
+                    server["_id"].as_str() == Some(key)
+                }
+            })
+            .cloned()
+    }
+
+    fn find_user(&self, id: &str) -> Option<&Value> {
+        self.fixtures
+            .users
+            .iter()
+            .find(|user| user["_id"].as_str() == Some(id))
+    }
+
+    /// The logged in user making the request.
+    fn authorize(&self, headers: &HeaderMap) -> Option<&Value> {
+        self.sessions
+            .verify(headers)
+            .and_then(|user_id| self.find_user(&user_id))
+    }
+}
+
+fn unauthorized() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({ "error": "Not logged in." })),
+    )
+        .into_response()
+}
+
+/// A fixture user as anyone can see it.
+fn public_profile(user: &Value) -> Value {
+    json!({
+        "_id": user["_id"],
+        "username": user["username"],
+        "rank": user["rank"],
+        "servers": user["servers"],
+        "cosmetics": user["cosmetics"],
+    })
+}
+
+/// A fixture user as Minehut shows it, without the mock only password.
+fn profile(user: &Value) -> Value {
+    let mut user = user.clone();
+    if let Some(user) = user.as_object_mut() {
+        user.remove("password");
+    }
+    user
+}
+
+pub fn router(config: MockConfig) -> Router {
+    let state = Arc::new(MockState {
+        servers: Mutex::new(config.fixtures.servers.clone()),
+        fixtures: config.fixtures,
+        scenario: Scenario::new(config.scenario),
+        sessions: Sessions::new(config.token_ttl_secs.map(Duration::from_secs)),
+        transitions: Mutex::new(HashMap::new()),
+        transition: Duration::from_millis(config.transition_millis.unwrap_or(2000)),
+    });
+
+    Router::new()
+        .route("/network/simple_stats", get(simple_stats))
+        .route("/server/{server}", get(server))
+        .route("/server/{server}/status", get(manage::status))
+        .route("/server/{server}/server_data", get(manage::server_data))
+        .route("/server/{server}/{action}", post(manage::action))
+        .route("/servers", get(servers))
+        .route("/plans", get(plans))
+        .route("/categories", get(categories))
+        .route("/icons", get(icons))
+        .route("/users/login", post(login))
+        .route("/users/{user}/profile", get(user_profile))
+        .route("/user/{user}", get(user))
+        .route("/user/{user}/servers", get(user_servers))
+        .with_state(state)
+}
+
+/// Serves the mock on `addr` until the process exits.
+pub async fn serve(addr: SocketAddr, config: MockConfig) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    log::info!(target: "MinehutMock", "Serving mock Minehut API on http://{}", listener.local_addr()?);
+    axum::serve(listener, router(config)).await
+}
+
+/// Serves the mock in the background, returning the address it bound to.
+///
+/// Bind to port 0 to let the OS pick a free port.
+pub async fn spawn(addr: SocketAddr, config: MockConfig) -> std::io::Result<SocketAddr> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, router(config)).await {
+            log::error!(target: "MinehutMock", "Mock Minehut API stopped: {err:?}");
+        }
+    });
+    Ok(addr)
+}
+
+/// Waits out any latency, then answers with the first response-replacing fault if there is one.
+async fn intercept(state: &MockState, uri: &Uri) -> Result<Outcome, Response> {
+    let mut outcome = state.scenario.next(uri.path());
+    log::debug!(target: "MinehutMock", "{} -> {outcome:?}", uri);
+    if !outcome.latency.is_zero() {
+        tokio::time::sleep(outcome.latency).await;
+    }
+
+    match outcome.response.take() {
+        None => Ok(outcome),
+        Some(Fault::Status { status, body }) => Err((
+            StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            body,
+        )
+            .into_response()),
+        Some(Fault::RateLimited { retry_after }) => {
+            let mut headers = HeaderMap::new();
+            if let Some(retry_after) = retry_after {
+                headers.insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+            }
+            Err((StatusCode::TOO_MANY_REQUESTS, headers, "Too many requests").into_response())
+        }
+        Some(Fault::Malformed) => Err((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json")],
+            r#"{"player_count": 12, "server_count": "#,
+        )
+            .into_response()),
+        Some(Fault::Latency { .. } | Fault::Offline { .. }) => Ok(outcome),
+    }
+}
+
+/// Answers `body` with an `ETag`, or with 304 Not Modified if the caller already has it.
+fn conditional(headers: &HeaderMap, body: Value) -> Response {
+    let mut hasher = DefaultHasher::new();
+    body.to_string().hash(&mut hasher);
+    let etag = format!("\"{:016x}\"", hasher.finish());
+
+    let matches = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+    let etag = HeaderValue::from_str(&etag).expect("ETags are ascii.");
+    if matches {
+        (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response()
+    } else {
+        ([(header::ETAG, etag)], Json(body)).into_response()
+    }
+}
+
+fn is_offline(outcome: &Outcome, server: &Value) -> bool {
+    server["name"]
+        .as_str()
+        .is_some_and(|name| outcome.offline.contains(&name.to_ascii_lowercase()))
+}
+
+fn take_offline(mut server: Value) -> Value {
+    server["online"] = json!(false);
+    server["playerCount"] = json!(0);
+    server
+}
+
+async fn simple_stats(
+    State(state): State<Arc<MockState>>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    match intercept(&state, &uri).await {
+        Ok(_) => conditional(&headers, state.fixtures.simple_stats.clone()),
+        Err(response) => response,
+    }
+}
+
+async fn plans(State(state): State<Arc<MockState>>, headers: HeaderMap, uri: Uri) -> Response {
+    match intercept(&state, &uri).await {
+        Ok(_) => conditional(&headers, json!({ "plans": state.fixtures.plans })),
+        Err(response) => response,
+    }
+}
+
+async fn categories(State(state): State<Arc<MockState>>, headers: HeaderMap, uri: Uri) -> Response {
+    match intercept(&state, &uri).await {
+        Ok(_) => conditional(&headers, json!({ "categories": state.fixtures.categories })),
+        Err(response) => response,
+    }
+}
+
+async fn icons(State(state): State<Arc<MockState>>, headers: HeaderMap, uri: Uri) -> Response {
+    match intercept(&state, &uri).await {
+        Ok(_) => conditional(&headers, json!({ "icons": state.fixtures.icons })),
+        Err(response) => response,
+    }
+}
+
+async fn server(
+    State(state): State<Arc<MockState>>,
+    Path(key): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    let outcome = match intercept(&state, &uri).await {
+        Ok(outcome) => outcome,
+        Err(response) => return response,
+    };
+
+    let by_name = query.get("byName").is_some_and(|value| value == "true");
+    match state.find_server(&key, by_name) {
+        Some(server) if is_offline(&outcome, &server) => {
+            conditional(&headers, json!({ "server": take_offline(server) }))
+        }
+        Some(server) => conditional(&headers, json!({ "server": server })),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Server not found." })),
+        )
+            .into_response(),
+    }
+}
+
+async fn servers(
+    State(state): State<Arc<MockState>>,
+    Query(query): Query<HashMap<String, String>>,
+    uri: Uri,
+) -> Response {
+    let outcome = match intercept(&state, &uri).await {
+        Ok(outcome) => outcome,
+        Err(response) => return response,
+    };
+
+    state.settle();
+    let servers = state.servers.lock().unwrap().clone();
+    let online = servers
+        .iter()
+        .filter(|server| server["online"].as_bool().unwrap_or(false))
+        .filter(|server| !is_offline(&outcome, server))
+        .collect::<Vec<_>>();
+    let total_players = online
+        .iter()
+        .map(|server| server["playerCount"].as_u64().unwrap_or(0))
+        .sum::<u64>();
+
+    let offset = query
+        .get("offset")
+        .and_then(|offset| offset.parse().ok())
+        .unwrap_or(0);
+    let limit = query
+        .get("limit")
+        .and_then(|limit| limit.parse().ok())
+        .unwrap_or(usize::MAX);
+    let page = online
+        .iter()
+        .skip(offset)
+        .take(limit)
+        .map(|server| listing(server))
+        .collect::<Vec<_>>();
+
+    Json(json!({
+        "servers": page,
+        "total_players": total_players,
+        "total_servers": online.len(),
+    }))
+    .into_response()
+}
+
+/// Reshapes a fixture server into how `/servers` lists it.
+fn listing(server: &Value) -> Value {
+    let mut categories = server["categories"].as_array().cloned().unwrap_or_default();
+    categories.extend(
+        server["inheritedCategories"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default(),
+    );
+
+    json!({
+        "staticInfo": {
+            "_id": server["_id"],
+            "serverPlan": server["server_plan"],
+            "serviceStartDate": server["last_online"],
+            "platform": server["platform"],
+            "planMaxPlayers": server["maxPlayers"],
+            "rawPlan": server["rawPlan"],
+            "connectedServers": server["connectedServers"],
+            "server_version_type": server["server_version_type"],
+        },
+        "maxPlayers": server["maxPlayers"],
+        "name": server["name"],
+        "motd": server["motd"],
+        "playerData": {
+            "playerCount": server["playerCount"],
+            "timeNoPlayers": 0,
+        },
+        "connectable": true,
+        "visibility": server["visibility"],
+        "allCategories": categories,
+        "author": server["owner"],
+    })
+}
+
+#[derive(serde_derive::Deserialize)]
+struct LoginRequest {
+    email: String,
+    password: String,
+}
+
+async fn login(
+    State(state): State<Arc<MockState>>,
+    uri: Uri,
+    Json(request): Json<LoginRequest>,
+) -> Response {
+    if let Err(response) = intercept(&state, &uri).await {
+        return response;
+    }
+
+    let user = state.fixtures.users.iter().find(|user| {
+        user["email"]
+            .as_str()
+            .is_some_and(|email| email.eq_ignore_ascii_case(&request.email))
+            && user["password"].as_str() == Some(request.password.as_str())
+    });
+    let Some(user_id) = user.and_then(|user| user["_id"].as_str()) else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "Invalid email or password." })),
+        )
+            .into_response();
+    };
+
+    let (token, session_id) = state.sessions.issue(user_id);
+    Json(json!({
+        "_id": user_id,
+        "token": token,
+        "session_id": session_id,
+    }))
+    .into_response()
+}
+
+async fn user(
+    State(state): State<Arc<MockState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    if let Err(response) = intercept(&state, &uri).await {
+        return response;
+    }
+
+    match state.authorize(&headers) {
+        Some(user) if user["_id"].as_str() == Some(id.as_str()) => {
+            Json(json!({ "user": profile(user) })).into_response()
+        }
+        Some(_) => (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "Not your account." })),
+        )
+            .into_response(),
+        None => unauthorized(),
+    }
+}
+
+async fn user_profile(
+    State(state): State<Arc<MockState>>,
+    Path(id): Path<String>,
+    uri: Uri,
+) -> Response {
+    if let Err(response) = intercept(&state, &uri).await {
+        return response;
+    }
+
+    match state.find_user(&id) {
+        Some(user) => Json(json!({ "profile": public_profile(user) })).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "User not found." })),
+        )
+            .into_response(),
+    }
+}
+
+async fn user_servers(
+    State(state): State<Arc<MockState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    if let Err(response) = intercept(&state, &uri).await {
+        return response;
+    }
+
+    match state.authorize(&headers) {
+        Some(user) if user["_id"].as_str() == Some(id.as_str()) => {
+            state.settle();
+            let servers = state
+                .servers
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|server| server["owner"] == user["_id"])
+                .cloned()
+                .collect::<Vec<_>>();
+            Json(json!({ "servers": servers })).into_response()
+        }
+        Some(_) => (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "Not your account." })),
+        )
+            .into_response(),
+        None => unauthorized(),
+    }
+}
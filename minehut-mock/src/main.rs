@@ -0,0 +1,40 @@
+use minehut_mock::MockConfig;
+use std::net::SocketAddr;
+
+const USAGE: &str = "Usage: minehut_mock [--bind <addr>] [--config <path>]";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    fern::Dispatch::new()
+        .format(|out, message, record| {
+            out.finish(format_args!(
+                "[{}] ([{}]/[{}]) {}",
+                record.level(),
+                chrono::Local::now().format("[%H:%M:%S]"),
+                record.target(),
+                message
+            ))
+        })
+        .level(log::LevelFilter::Info)
+        .level_for("MinehutMock", log::LevelFilter::Debug)
+        .chain(std::io::stdout())
+        .apply()?;
+
+    let mut bind = SocketAddr::from(([127, 0, 0, 1], 8080));
+    let mut config = MockConfig::default();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--bind", Some(addr)) => bind = addr.parse()?,
+            ("--config", Some(path)) => config = MockConfig::from_file(path)?,
+            _ => {
+                eprintln!("{USAGE}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    minehut_mock::serve(bind, config).await?;
+    Ok(())
+}
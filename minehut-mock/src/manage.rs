@@ -0,0 +1,223 @@
+use crate::{intercept, MockState};
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Where a server ends up once a start, stop or hibernate finishes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Target {
+    Online,
+    Offline,
+    Hibernating,
+}
+
+#[derive(Debug)]
+pub(crate) struct Transition {
+    target: Target,
+    until: Instant,
+}
+
+impl MockState {
+    /// Finishes every start, stop and hibernate which has taken long enough.
+    pub(crate) fn settle(&self) {
+        let mut transitions = self.transitions.lock().unwrap();
+        let mut servers = self.servers.lock().unwrap();
+        transitions.retain(|id, transition| {
+            if transition.until > Instant::now() {
+                return true;
+            }
+            if let Some(server) = servers
+                .iter_mut()
+                .find(|server| server["_id"].as_str() == Some(id))
+            {
+                server["online"] = json!(transition.target == Target::Online);
+                server["service_online"] = json!(transition.target != Target::Hibernating);
+                if transition.target != Target::Online {
+                    server["playerCount"] = json!(0);
+                }
+            }
+            false
+        });
+    }
+
+    fn begin(&self, id: &str, target: Target) {
+        if let Some(server) = self
+            .servers
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|server| server["_id"].as_str() == Some(id))
+        {
+            server["online"] = json!(false);
+            server["playerCount"] = json!(0);
+        }
+        self.transitions.lock().unwrap().insert(
+            id.to_string(),
+            Transition {
+                target,
+                until: Instant::now() + self.transition,
+            },
+        );
+    }
+
+    fn edit(&self, id: &str, edit: impl FnOnce(&mut Value)) {
+        if let Some(server) = self
+            .servers
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|server| server["_id"].as_str() == Some(id))
+        {
+            edit(server);
+        }
+    }
+
+    /// The server with `_id` `id`, as long as the logged in user owns it.
+    fn owned(&self, headers: &HeaderMap, id: &str) -> Result<Value, StatusCode> {
+        let user = self.authorize(headers).ok_or(StatusCode::UNAUTHORIZED)?;
+        let server = self.find_server(id, false).ok_or(StatusCode::NOT_FOUND)?;
+        if server["owner"] != user["_id"] {
+            return Err(StatusCode::FORBIDDEN);
+        }
+        Ok(server)
+    }
+}
+
+fn error(status: StatusCode) -> Response {
+    let message = match status {
+        StatusCode::UNAUTHORIZED => "Not logged in.",
+        StatusCode::FORBIDDEN => "You do not own this server.",
+        StatusCode::NOT_FOUND => "Not found.",
+        _ => "Invalid request.",
+    };
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+fn properties(server: &Value) -> Value {
+    match &server["server_properties"] {
+        Value::Object(_) => server["server_properties"].clone(),
+        _ => json!({
+            "difficulty": "easy",
+            "gamemode": "survival",
+            "level_type": "default",
+            "max_players": server["maxPlayers"],
+            "pvp": true,
+            "spawn_protection": 16,
+            "view_distance": 10,
+            "allow_flight": false,
+            "announce_player_achievements": true,
+        }),
+    }
+}
+
+pub(crate) async fn status(
+    State(state): State<Arc<MockState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    if let Err(response) = intercept(&state, &uri).await {
+        return response;
+    }
+    let server = match state.owned(&headers, &id) {
+        Ok(server) => server,
+        Err(status) => return error(status),
+    };
+
+    let transition = state
+        .transitions
+        .lock()
+        .unwrap()
+        .get(&id)
+        .map(|transition| transition.target);
+    let online = server["online"].as_bool().unwrap_or(false);
+    let service_online = server["service_online"].as_bool().unwrap_or(online);
+    let status = match transition {
+        Some(Target::Online) => "STARTING",
+        Some(_) => "STOPPING",
+        None if online => "ONLINE",
+        None if service_online => "OFFLINE",
+        None => "HIBERNATING",
+    };
+
+    Json(json!({
+        "status": {
+            "online": online,
+            "service_online": service_online,
+            "status": status,
+            "starting": transition == Some(Target::Online),
+            "stopping": transition.is_some_and(|target| target != Target::Online),
+            "player_count": server["playerCount"],
+        }
+    }))
+    .into_response()
+}
+
+pub(crate) async fn server_data(
+    State(state): State<Arc<MockState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    uri: Uri,
+) -> Response {
+    if let Err(response) = intercept(&state, &uri).await {
+        return response;
+    }
+    match state.owned(&headers, &id) {
+        Ok(mut server) => {
+            server["server_properties"] = properties(&server);
+            Json(json!({ "server": server })).into_response()
+        }
+        Err(status) => error(status),
+    }
+}
+
+pub(crate) async fn action(
+    State(state): State<Arc<MockState>>,
+    Path((id, action)): Path<(String, String)>,
+    headers: HeaderMap,
+    uri: Uri,
+    body: Bytes,
+) -> Response {
+    if let Err(response) = intercept(&state, &uri).await {
+        return response;
+    }
+    let server = match state.owned(&headers, &id) {
+        Ok(server) => server,
+        Err(status) => return error(status),
+    };
+    let body = serde_json::from_slice::<Value>(&body).unwrap_or(Value::Null);
+
+    match action.as_str() {
+        "start_service" | "restart" => state.begin(&id, Target::Online),
+        "shutdown" => state.begin(&id, Target::Offline),
+        "destroy_service" => state.begin(&id, Target::Hibernating),
+        "edit_server_properties" => {
+            let Some(field) = body["field"].as_str() else {
+                return error(StatusCode::BAD_REQUEST);
+            };
+            let mut properties = properties(&server);
+            properties[field] = body["value"].clone();
+            state.edit(&id, |server| server["server_properties"] = properties);
+        }
+        "change_motd" => {
+            let Some(motd) = body["motd"].as_str() else {
+                return error(StatusCode::BAD_REQUEST);
+            };
+            state.edit(&id, |server| server["motd"] = json!(motd));
+        }
+        "visibility" => {
+            let Some(visibility) = body["visibility"].as_bool() else {
+                return error(StatusCode::BAD_REQUEST);
+            };
+            state.edit(&id, |server| server["visibility"] = json!(visibility));
+        }
+        _ => return error(StatusCode::NOT_FOUND),
+    }
+
+    Json(json!({})).into_response()
+}
@@ -0,0 +1,103 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Something going wrong on purpose, applied to the requests a [`Rule`] matches.
+#[derive(serde_derive::Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Fault {
+    /// Waits before answering, stacks with every other active fault.
+    Latency { millis: u64 },
+    /// Answers with `status` instead of the fixture, ex: a 5xx burst.
+    Status {
+        status: u16,
+        #[serde(default)]
+        body: String,
+    },
+    /// Answers with a 429, optionally with a `Retry-After` in seconds.
+    RateLimited {
+        #[serde(default)]
+        retry_after: Option<u64>,
+    },
+    /// Answers with a 200 whose body is not valid JSON.
+    Malformed,
+    /// Serves `server` as offline with no players, and leaves it out of the server list.
+    Offline { server: String },
+}
+
+/// Applies `fault` to matching requests number `after` through `after + times`, counted from 0.
+///
+/// Without `times` the fault stays active for every request after that.
+#[derive(serde_derive::Deserialize, Clone, Debug)]
+pub struct Rule {
+    /// Only requests whose path starts with this count towards the rule.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub after: u64,
+    #[serde(default)]
+    pub times: Option<u64>,
+    pub fault: Fault,
+}
+
+impl Rule {
+    fn matches(&self, path: &str) -> bool {
+        self.endpoint
+            .as_ref()
+            .is_none_or(|endpoint| path.starts_with(endpoint.as_str()))
+    }
+
+    fn is_active(&self, request: u64) -> bool {
+        request >= self.after && self.times.is_none_or(|times| request < self.after + times)
+    }
+}
+
+/// What the active faults mean for one request.
+#[derive(Debug, Default)]
+pub struct Outcome {
+    pub latency: Duration,
+    /// The first fault which replaces the fixture response.
+    pub response: Option<Fault>,
+    pub offline: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Scenario {
+    rules: Vec<Rule>,
+    counters: Mutex<Vec<u64>>,
+}
+
+impl Scenario {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        Self {
+            counters: Mutex::new(vec![0; rules.len()]),
+            rules,
+        }
+    }
+
+    /// Counts a request to `path` against every matching rule.
+    pub fn next(&self, path: &str) -> Outcome {
+        let mut counters = self.counters.lock().unwrap();
+        let mut outcome = Outcome::default();
+        for (rule, counter) in self.rules.iter().zip(counters.iter_mut()) {
+            if !rule.matches(path) {
+                continue;
+            }
+            let request = *counter;
+            *counter += 1;
+            if !rule.is_active(request) {
+                continue;
+            }
+
+            match &rule.fault {
+                Fault::Latency { millis } => outcome.latency += Duration::from_millis(*millis),
+                Fault::Offline { server } => outcome.offline.push(server.to_ascii_lowercase()),
+                fault => {
+                    if outcome.response.is_none() {
+                        outcome.response = Some(fault.clone());
+                    }
+                }
+            }
+        }
+        outcome
+    }
+}
@@ -0,0 +1,206 @@
+use futures::TryStreamExt;
+use minehut_api::prelude::*;
+use minehut_mock::scenario::Rule;
+use minehut_mock::MockConfig;
+use std::time::Duration;
+
+async fn mock(scenario: serde_json::Value) -> MinehutClientBuilder {
+    let config = MockConfig {
+        scenario: serde_json::from_value::<Vec<Rule>>(scenario).unwrap(),
+        ..MockConfig::default()
+    };
+    let addr = minehut_mock::spawn("127.0.0.1:0".parse().unwrap(), config)
+        .await
+        .unwrap();
+    MinehutClient::builder().api(format!("http://{addr}"))
+}
+
+#[tokio::test]
+async fn faults_apply_to_the_counted_requests_only() {
+    let client = mock(serde_json::json!([{
+        "endpoint": "/network/simple_stats",
+        "after": 1,
+        "times": 2,
+        "fault": { "kind": "status", "status": 503, "body": "Service Unavailable" }
+    }]))
+    .await
+    .build()
+    .unwrap();
+
+    assert!(client.get_simple_stats().await.is_ok());
+    for _ in 0..2 {
+        match client.get_simple_stats().await {
+            Err(MinehutError::Status { status, body, .. }) => {
+                assert_eq!(status, 503);
+                assert_eq!(body, "Service Unavailable");
+            }
+            other => panic!("expected a 503, got {other:?}"),
+        }
+    }
+    assert!(client.get_simple_stats().await.is_ok());
+}
+
+#[tokio::test]
+async fn faults_only_match_their_endpoint() {
+    let client = mock(serde_json::json!([{
+        "endpoint": "/server/",
+        "fault": { "kind": "status", "status": 500 }
+    }]))
+    .await
+    .build()
+    .unwrap();
+
+    assert!(client.get_simple_stats().await.is_ok());
+    assert!(matches!(
+        client.get_server_by_name("MockHub").await,
+        Err(MinehutError::Status { status: 500, .. })
+    ));
+}
+
+#[tokio::test]
+async fn server_errors_are_retried() {
+    let client = mock(serde_json::json!([{
+        "endpoint": "/network/simple_stats",
+        "times": 2,
+        "fault": { "kind": "status", "status": 502 }
+    }]))
+    .await
+    .retry_policy(
+        RetryPolicy::default()
+            .max_attempts(3)
+            .base_delay(Duration::from_millis(1)),
+    )
+    .build()
+    .unwrap();
+
+    assert!(client.get_simple_stats().await.is_ok());
+}
+
+#[tokio::test]
+async fn rate_limits_carry_retry_after() {
+    let client = mock(serde_json::json!([{
+        "endpoint": "/network/simple_stats",
+        "times": 1,
+        "fault": { "kind": "rate_limited", "retry_after": 2 }
+    }]))
+    .await
+    .build()
+    .unwrap();
+
+    match client.get_simple_stats().await {
+        Err(MinehutError::RateLimited { retry_after, .. }) => {
+            assert_eq!(retry_after, Some(Duration::from_secs(2)));
+        }
+        other => panic!("expected a 429, got {other:?}"),
+    }
+    assert!(client.get_simple_stats().await.is_ok());
+}
+
+#[tokio::test]
+async fn rate_limits_are_retried_after_a_capped_wait() {
+    let client = mock(serde_json::json!([{
+        "endpoint": "/network/simple_stats",
+        "times": 1,
+        "fault": { "kind": "rate_limited", "retry_after": 60 }
+    }]))
+    .await
+    .retry_policy(
+        RetryPolicy::default()
+            .max_attempts(2)
+            .max_delay(Duration::from_millis(10)),
+    )
+    .build()
+    .unwrap();
+
+    let stats = tokio::time::timeout(Duration::from_secs(5), client.get_simple_stats())
+        .await
+        .expect("Retry-After should be capped at max_delay");
+    assert!(stats.is_ok());
+}
+
+#[tokio::test]
+async fn malformed_bodies_fail_to_decode() {
+    let client = mock(serde_json::json!([{
+        "endpoint": "/network/simple_stats",
+        "times": 1,
+        "fault": { "kind": "malformed" }
+    }]))
+    .await
+    .build()
+    .unwrap();
+
+    assert!(matches!(
+        client.get_simple_stats().await,
+        Err(MinehutError::Decode { .. })
+    ));
+    assert!(client.get_simple_stats().await.is_ok());
+}
+
+#[tokio::test]
+async fn offline_servers_are_hidden() {
+    let client = mock(serde_json::json!([{
+        "fault": { "kind": "offline", "server": "mocksurvival" }
+    }]))
+    .await
+    .build()
+    .unwrap();
+
+    let server = client.get_server_by_name("MockSurvival").await.unwrap();
+    assert_eq!(server.online, Some(false));
+    assert_eq!(server.player_count, Some(0.0));
+
+    let listed = client
+        .list_servers(ServerListFilter::default())
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert!(!listed.is_empty());
+    assert!(listed
+        .iter()
+        .all(|server| server.name.as_deref() != Some("MockSurvival")));
+}
+
+#[tokio::test]
+async fn latency_can_time_out_calls() {
+    let client = mock(serde_json::json!([{
+        "endpoint": "/network/simple_stats",
+        "times": 1,
+        "fault": { "kind": "latency", "millis": 500 }
+    }]))
+    .await
+    .timeout(Duration::from_millis(100))
+    .build()
+    .unwrap();
+
+    let err = client.get_simple_stats().await.unwrap_err();
+    assert!(err.is_timeout(), "{err:?}");
+    assert!(client.get_simple_stats().await.is_ok());
+}
+
+#[tokio::test]
+async fn huge_retry_afters_only_block_for_max_delay() {
+    let client = mock(serde_json::json!([{
+        "endpoint": "/network/simple_stats",
+        "times": 1,
+        "fault": { "kind": "rate_limited", "retry_after": u64::MAX }
+    }]))
+    .await
+    .rate_limit(100.0, 10)
+    .retry_policy(
+        RetryPolicy::default()
+            .max_attempts(2)
+            .max_delay(Duration::from_millis(10)),
+    )
+    .build()
+    .unwrap();
+
+    let stats = tokio::time::timeout(Duration::from_secs(5), client.get_simple_stats())
+        .await
+        .expect("the rate limiter should only block for max_delay");
+    assert!(stats.is_ok());
+    // the limiter no longer holds up other calls
+    let server = tokio::time::timeout(Duration::from_secs(1), client.get_server_by_name("MockHub"))
+        .await
+        .expect("the rate limiter should have been released");
+    assert!(server.is_ok());
+}
@@ -1,5 +1,5 @@
 #!/bin/bash
 
 export RUSTBACKTRACE=1
-export MINEHUT_URL="api.minehut.com"
+# the Minehut API is picked by `api_environment` in etc/config.json, then MINEHUT_URL, then prod
 cargo run
\ No newline at end of file
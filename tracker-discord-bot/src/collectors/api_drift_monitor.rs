@@ -0,0 +1,45 @@
+use crate::TypeMap;
+use minehut_api::prelude::DriftReport;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use serenity::utils::MessageBuilder;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+pub struct DriftReportKey;
+
+impl serenity::prelude::TypeMapKey for DriftReportKey {
+    type Value = flume::Receiver<DriftReport>;
+}
+
+pub async fn setup(type_map: Arc<RwLock<TypeMap>>, http: Arc<Http>) {
+    let read_lock = type_map.read().await;
+
+    let configuration = read_lock.get::<crate::ConfigurationTypeKey>().unwrap();
+    let channel = ChannelId::from(configuration.tools_channel());
+    let receiver = read_lock.get::<DriftReportKey>().unwrap().clone();
+    drop(read_lock);
+
+    log::info!(target: "ApiDrift/Collector", "API drift collector looping");
+    while let Ok(report) = receiver.recv_async().await {
+        let changes = report
+            .changes
+            .iter()
+            .map(|change| change.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let alert = MessageBuilder::new()
+            .push_bold_safe("Minehut API shape changed")
+            .push(" for ")
+            .push_mono_safe(&report.endpoint)
+            .push_line("")
+            .push_codeblock_safe(changes, Some("diff"))
+            .build();
+
+        if let Err(err) = channel.say(&http, &alert).await {
+            log::error!(target: "ApiDrift/Collector", "Error sending API drift alert: {err:?}");
+        }
+    }
+    log::error!(target: "ApiDrift/Collector", "Some error occurred during processing of flume messenger.");
+}
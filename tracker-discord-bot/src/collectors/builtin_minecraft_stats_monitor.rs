@@ -1,3 +1,4 @@
+use crate::minecraft_status::ServerStatus;
 use crate::{embed, TypeMap};
 use serenity::cache::Cache;
 use serenity::http::Http;
@@ -32,6 +33,7 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
 
     let mut poll_index = 0u8;
     let mut past_network_sheet: Option<NetworkStatsSheet> = None;
+    let mut past_status: Option<ServerStatus> = None;
     let mut network_info = Vec::with_capacity(POLL_PERIOD_SIZE as usize);
     let mut last_poll_time = Instant::now();
 
@@ -54,7 +56,8 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
                         accum.3 + 1,
                     ),
                 });
-            let true_poll_size = (POLL_PERIOD_SIZE - (POLL_PERIOD_SIZE - sheet.3)) as usize;
+            // a period where every poll failed still updates the embed, with zeroed stats
+            let true_poll_size = (POLL_PERIOD_SIZE - (POLL_PERIOD_SIZE - sheet.3)).max(1) as usize;
             let sheet = NetworkStatsSheet {
                 player_count: sheet.0 / true_poll_size,
                 server_count: sheet.1 / true_poll_size,
@@ -62,6 +65,33 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
                 successful_calls: sheet.3,
             };
 
+            // the latest successful poll, for what can't be averaged
+            let status = network_info
+                .iter()
+                .rev()
+                .flatten()
+                .next()
+                .map(|stats| stats.status.clone());
+            if let (Some(past), Some(status)) = (&past_status, &status) {
+                if past.version != status.version {
+                    log::info!(target: "MinecraftStats/Collector", "Version changed from {:?} to {:?}", past.version, status.version);
+                }
+                if past.description != status.description {
+                    log::info!(target: "MinecraftStats/Collector", "MOTD changed to {:?}", status.description.plain_text());
+                }
+            }
+            let (version, secure_chat) = match &status {
+                None => ("unknown".to_string(), "unknown"),
+                Some(status) => (
+                    format!("{} ({})", status.version.name, status.version.protocol),
+                    match status.enforces_secure_chat {
+                        Some(true) => "enforced",
+                        Some(false) => "not enforced",
+                        None => "unknown",
+                    },
+                ),
+            };
+
             let me = cache_and_http.0.current_user();
 
             let header = format!(
@@ -76,6 +106,10 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
                 _Time since last embed update_: <t:{}:R>
 
                 _Successful Operations_: `({}/{POLL_PERIOD_SIZE})`
+
+                _Version_: `{version}`
+
+                _Secure Chat_: `{secure_chat}`
                 "#,
                 sheet.latency,
                 last_poll_time.elapsed().as_secs(),
@@ -83,7 +117,7 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
                 sheet.successful_calls
             );
 
-            let embed = match past_network_sheet {
+            let mut embed = match past_network_sheet {
                 None => {
                     embed!(embed {
                         author {
@@ -165,6 +199,25 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
                     embed
                 }
             };
+            if let Some(status) = &status {
+                // fields are capped at 1024 characters
+                let motd = status.description.plain_text();
+                let motd = motd.trim().chars().take(1000).collect::<String>();
+                if !motd.is_empty() {
+                    embed.field("MOTD", format!("```\n{motd}\n```"), false);
+                }
+                let sample = status
+                    .players
+                    .sample
+                    .iter()
+                    .map(|player| crate::minecraft_status::strip_formatting_codes(&player.name))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let sample = sample.trim().chars().take(1000).collect::<String>();
+                if !sample.is_empty() {
+                    embed.field("Player Sample", sample, false);
+                }
+            }
 
             if let Err(err) = channel
                 .edit_message(&cache_and_http.1, message, |message| {
@@ -176,6 +229,7 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
             }
 
             past_network_sheet = Some(sheet);
+            past_status = status.or(past_status);
             poll_index = 0;
             network_info.clear();
             last_poll_time = Instant::now()
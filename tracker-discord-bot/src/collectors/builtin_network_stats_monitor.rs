@@ -1,8 +1,11 @@
 use crate::{embed, TypeMap};
+use minehut_api::snapshot::SnapshotWriter;
 use serenity::cache::Cache;
 use serenity::http::Http;
 use serenity::model::id::{ChannelId, MessageId};
 use serenity::utils::Color;
+use std::fs::OpenOptions;
+use std::io::BufWriter;
 use std::ops::Div;
 use std::sync::Arc;
 use std::time::Instant;
@@ -22,6 +25,18 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
     let configuration = read_lock.get::<crate::ConfigurationTypeKey>().unwrap();
     let channel = ChannelId::from(configuration.builtin_network_stats_monitor_channel);
     let message = MessageId::from(configuration.builtin_network_stats_monitor_message);
+    let mut archive = configuration
+        .builtin_network_stats_monitor_archive
+        .as_ref()
+        .and_then(|path| {
+            match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => Some(SnapshotWriter::new(BufWriter::new(file))),
+                Err(err) => {
+                    log::error!(target: "NetworkStats/Collector", "Failed to open snapshot archive {path}: {err:?}");
+                    None
+                }
+            }
+        });
     let receiver = read_lock
         .get::<crate::reporters::NetworkStatsReporterKey>()
         .unwrap()
@@ -35,6 +50,11 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
 
     log::info!(target: "NetworkStats/Collector", "Network stats collector looping");
     while let Ok(response) = receiver.recv_async().await {
+        if let (Some(archive), Some(stats)) = (archive.as_mut(), response.as_ref()) {
+            if let Err(err) = archive.record("/network/simple_stats", stats) {
+                log::error!(target: "NetworkStats/Collector", "Failed to archive network stats: {err:?}");
+            }
+        }
         network_info.push(response);
         poll_index += 1;
         log::info!(target: "NetworkStats/Collector", "Received a network stats event.");
@@ -63,7 +83,7 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
             let header = format!(
                 r#"**Minehut Network API Monitor**
 
-                _API Call_: GET `https://api.minehut.com/network/simple_stats`
+                _API Call_: GET `{}`
 
                 _Time since last update_: `{} seconds`
 
@@ -71,6 +91,7 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
 
                 _Successful Operations_: `({}/{POLL_PERIOD_SIZE})`
                 "#,
+                minehut_api::client::default_client().url_for("/network/simple_stats"),
                 last_poll_time.elapsed().as_secs(),
                 crate::minecraft_bot::get_system_time_as_millis() / 1000,
                 sheet.successful_calls
@@ -168,6 +189,10 @@ pub async fn setup(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>,
                 log::error!(target: "NetworkStats/Collector", "Error editing network stats monitor message: {err:?}");
             }
 
+            if let Some(Err(err)) = archive.as_mut().map(|archive| archive.flush()) {
+                log::error!(target: "NetworkStats/Collector", "Failed to flush snapshot archive: {err:?}");
+            }
+
             past_network_sheet = Some(sheet);
             poll_index = 0;
             network_info.clear();
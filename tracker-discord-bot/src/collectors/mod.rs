@@ -4,9 +4,12 @@ use serenity::http::Http;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+mod api_drift_monitor;
 mod builtin_minecraft_stats_monitor;
 mod builtin_network_stats_monitor;
 
+pub use api_drift_monitor::DriftReportKey;
+
 pub async fn configure(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cache>, Arc<Http>)) {
     let map_clone = Arc::clone(&type_map);
     let cache_clone = Arc::clone(&cache_and_http.0);
@@ -20,4 +23,7 @@ pub async fn configure(type_map: Arc<RwLock<TypeMap>>, cache_and_http: (Arc<Cach
     tokio::spawn(async move {
         builtin_minecraft_stats_monitor::setup(map_clone, (cache_clone, http_clone)).await
     });
+    let map_clone = Arc::clone(&type_map);
+    let http_clone = Arc::clone(&cache_and_http.1);
+    tokio::spawn(async move { api_drift_monitor::setup(map_clone, http_clone).await });
 }
@@ -1,12 +1,16 @@
 use crate::embed;
 use anyhow::Context;
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Utc};
+use minehut_api::prelude::{
+    Catalog, Category, EndpointMetrics, Method, MinehutError, ServerPlan, ServerQuery,
+};
 use serenity::builder::CreateEmbed;
 use serenity::model::application::command::{Command, CommandOptionType};
 use serenity::model::application::interaction::application_command::{
     ApplicationCommandInteraction, CommandDataOptionValue,
 };
 use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::permissions::Permissions;
 use serenity::utils::Color;
 
 type ApplicationCommandFuture = crate::event_handler::ApplicationCommandFuture;
@@ -39,37 +43,238 @@ async fn ack_embed(
         .context("Failed to send interaction message.")
 }
 
+/// Acknowledges the interaction without a message, for commands which can take longer than
+/// Discord's 3 second deadline. The answer is sent with [`edit_embed`].
+async fn ack_deferred(
+    ctx: &serenity::client::Context,
+    interaction: &ApplicationCommandInteraction,
+) -> anyhow::Result<()> {
+    interaction
+        .create_interaction_response(&ctx.http, |res| {
+            res.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+        })
+        .await
+        .context("Failed to defer interaction message.")
+}
+
+async fn edit_embed(
+    ctx: &serenity::client::Context,
+    interaction: &ApplicationCommandInteraction,
+    embed: CreateEmbed,
+) -> anyhow::Result<()> {
+    interaction
+        .edit_original_interaction_response(&ctx.http, |message| message.set_embed(embed))
+        .await
+        .map(|_| ())
+        .context("Failed to edit interaction message.")
+}
+
+fn api_error_message(err: &MinehutError) -> String {
+    match err {
+        MinehutError::NotFound { path } => format!("Minehut has nothing at `{path}`."),
+        MinehutError::Unauthorized { .. } => {
+            String::from("Minehut refused our login, check the configured credentials.")
+        }
+        MinehutError::RateLimited {
+            retry_after: Some(retry_after),
+            ..
+        } => format!(
+            "Minehut is rate limiting us, try again in {} seconds.",
+            retry_after.as_secs().max(1)
+        ),
+        MinehutError::RateLimited { .. } => {
+            String::from("Minehut is rate limiting us, try again shortly.")
+        }
+        MinehutError::Status { status, .. } if *status >= 500 => {
+            format!("Minehut's API is having trouble right now ({status}).")
+        }
+        MinehutError::Status { status, .. } => {
+            format!("Minehut's API rejected the request ({status}).")
+        }
+        MinehutError::NotRecorded { path, .. } => {
+            format!("The API cassette has no recording for `{path}`.")
+        }
+        MinehutError::Encode { .. } => String::from("The request could not be encoded."),
+        MinehutError::Decode { field, .. } => {
+            format!("Minehut returned data we could not understand (at `{field}`).")
+        }
+        err if err.is_timeout() => String::from("Minehut's API took too long to respond."),
+        MinehutError::Transport { .. } | MinehutError::Build(_) => {
+            String::from("Could not reach Minehut's API.")
+        }
+    }
+}
+
+/// Where the api serves `server_name`, queried the same way as the client's own lookups.
+fn server_url(server_name: &str) -> String {
+    let client = minehut_api::client::default_client();
+    let call = client
+        .call(format!("/server/{server_name}"))
+        .query(&ServerQuery { by_name: true });
+    client.url_for(call.path())
+}
+
+fn metrics_summary(metrics: &EndpointMetrics) -> String {
+    let millis = |duration: Option<std::time::Duration>| match duration {
+        Some(duration) => format!("{}ms", duration.as_millis()),
+        None => String::from("-"),
+    };
+    let statuses = metrics
+        .statuses
+        .iter()
+        .map(|(status, count)| format!("{status}: {count}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "{} calls ({} cached), {:.1}% errors\np50 {} / p95 {} / p99 {} / max {}\n{}",
+        metrics.calls,
+        metrics.cached,
+        metrics.error_rate() * 100.0,
+        millis(metrics.latency.percentile(0.5)),
+        millis(metrics.latency.percentile(0.95)),
+        millis(metrics.latency.percentile(0.99)),
+        millis(metrics.latency.max()),
+        if statuses.is_empty() {
+            String::from("No responses")
+        } else {
+            statuses
+        }
+    )
+}
+
+fn string_option<'a>(
+    interaction: &'a ApplicationCommandInteraction,
+    name: &str,
+) -> Option<&'a str> {
+    interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == name)
+        .and_then(|option| option.resolved.as_ref())
+        .and_then(|value| match value {
+            CommandDataOptionValue::String(value) => Some(value.as_str()),
+            _ => None,
+        })
+}
+
+fn bool_option(interaction: &ApplicationCommandInteraction, name: &str) -> Option<bool> {
+    interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == name)
+        .and_then(|option| option.resolved.as_ref())
+        .and_then(|value| match value {
+            CommandDataOptionValue::Boolean(value) => Some(*value),
+            _ => None,
+        })
+}
+
+/// A plan's display name and price, ex: `Starter (MH20, 2048MB, $6.99/mo)`.
+fn plan_label(catalog: &Catalog, plan: Option<&ServerPlan>) -> String {
+    let Some(plan) = plan else {
+        return String::from("Unknown");
+    };
+    let Some(details) = catalog.plan(plan) else {
+        return plan.to_string();
+    };
+
+    let mut label = format!("{} ({plan}", details.display_name());
+    if let Some(ram) = details.ram {
+        label.push_str(&format!(", {ram:.0}MB"));
+    }
+    if let Some(price) = details.price {
+        label.push_str(&format!(", ${price:.2}/mo"));
+    }
+    label.push(')');
+    label
+}
+
+fn category_labels(catalog: &Catalog, categories: &[String]) -> String {
+    categories
+        .iter()
+        .map(|category| {
+            catalog
+                .category(category)
+                .map_or(category.as_str(), Category::display_name)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 pub fn raw_call_command_handle(
     ctx: serenity::client::Context,
     interaction: ApplicationCommandInteraction,
 ) -> ApplicationCommandFuture {
     Box::pin(async move {
         let me = ctx.cache.current_user();
-        let path_data = interaction
-            .data
-            .options
-            .first()
-            .unwrap()
-            .resolved
-            .as_ref()
-            .unwrap();
-
-        if let CommandDataOptionValue::String(path_data) = path_data {
-            let response = minehut_api::rest::Call::new(path_data)
-                .get::<serde_json::value::Value>()
-                .await?;
-            ack_content(&ctx, &interaction, format!("```json\n{response:#?}```")).await
-        } else {
+        let failure = |description: String| {
             embed!(failure {
                 author {
                     name: (&me.name)
                     icon: (me.avatar_url().as_ref().unwrap())
                 }
-                description: ("**Failed to resolve path value.**")
+                description: (description)
                 color: (Color::DARK_RED)
             });
+            failure
+        };
 
-            ack_embed(&ctx, &interaction, failure).await
+        let Some(path_data) = string_option(&interaction, "path") else {
+            return ack_embed(
+                &ctx,
+                &interaction,
+                failure(String::from("**Failed to resolve path value.**")),
+            )
+            .await;
+        };
+        let method = string_option(&interaction, "method").unwrap_or("GET");
+        let Ok(method) = Method::from_bytes(method.as_bytes()) else {
+            return ack_embed(
+                &ctx,
+                &interaction,
+                failure(format!("**Unknown method {method}.**")),
+            )
+            .await;
+        };
+
+        let mut call = minehut_api::rest::Call::new(path_data);
+        if let Some(body) = string_option(&interaction, "body") {
+            match serde_json::from_str::<serde_json::Value>(body) {
+                Ok(body) => call = call.json(&body),
+                Err(err) => {
+                    return ack_embed(
+                        &ctx,
+                        &interaction,
+                        failure(format!("**Body is not valid JSON.** {err}")),
+                    )
+                    .await;
+                }
+            }
+        }
+
+        match call.send::<serde_json::value::Value>(method.clone()).await {
+            Ok(response) => {
+                ack_content(
+                    &ctx,
+                    &interaction,
+                    format!(
+                        "`{} {path_data}` -> `{}`\n```json\n{:#?}```",
+                        method, response.status, response.data
+                    ),
+                )
+                .await
+            }
+            Err(err) => {
+                log::warn!("Raw call {method} {path_data} failed: {err:?}");
+                ack_embed(
+                    &ctx,
+                    &interaction,
+                    failure(format!("**{}**", api_error_message(&err))),
+                )
+                .await
+            }
         }
     })
 }
@@ -127,113 +332,168 @@ pub fn server_command_handler(
             .unwrap();
 
         if let CommandDataOptionValue::String(server_name) = server_name {
+            // the owner, catalogs and suggestions can take longer than Discord waits for an answer
+            ack_deferred(&ctx, &interaction).await?;
+
             let response = minehut_api::rest::get_server_by_name(server_name).await;
             let ack = match response {
                 Ok(server) => {
-                    let naive = NaiveDateTime::from_timestamp(
-                        (server.creation.unwrap_or(0.0) as i64) / 1_000,
-                        0,
-                    );
-                    let datetime: DateTime<Utc> = DateTime::from_utc(naive, Utc);
-                    let date = datetime.format("%Y-%m-%d %H:%M:%S");
+                    // falls back to the raw owner id when the profile can't be resolved
+                    let owner = minehut_api::client::default_client()
+                        .get_server_owner(&server)
+                        .await
+                        .map(|owner| owner.display_name().to_string())
+                        .unwrap_or_else(|err| {
+                            log::warn!("Failed to resolve the owner of {server_name}: {err:?}");
+                            server
+                                .owner
+                                .clone()
+                                .unwrap_or_else(|| String::from("Unknown"))
+                        });
+                    let server = server.typed();
+                    let catalog = minehut_api::catalog::get_catalog()
+                        .await
+                        .unwrap_or_else(|err| {
+                            log::warn!("Failed to fetch Minehut's catalog: {err:?}");
+                            Catalog::default()
+                        });
+                    let format_date = |date: Option<DateTime<Utc>>| {
+                        date.map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+                            .unwrap_or_else(|| String::from("Unknown"))
+                    };
 
                     embed!(response_embed {
                         author {
                             name: ("Click here for more information")
                             icon: (me.avatar_url().as_ref().unwrap())
-                            url: (format!("https://{}/server/{}?byName=true", std::env::var("MINEHUT_URL").unwrap_or_else(|_| "api.dev.minehut.com".to_string()), server_name))
+                            url: (server_url(server_name))
                         }
                         description: (format!("**Successfully resolved server {}[{}]**", server.name.as_ref().unwrap_or(server_name), &server.id))
+                        field {
+                            name: ("Owner")
+                            value: (owner)
+                            inline: true;
+                        }
                         field {
                             name: ("Visibility")
-                            value: (format!("{}", server.visibility.unwrap_or(false)))
+                            value: (format!("{}", server.visibility))
                             inline: true;
                         }
                         field {
                             name: ("Suspended")
-                            value: (format!("{}", server.suspended.unwrap_or(false)))
+                            value: (format!("{}", server.suspended))
                             inline: true;
                         }
                         field {
                             name: ("Online")
-                            value: (format!("{}", server.online.unwrap_or(false)))
+                            value: (format!("{}", server.online))
                             inline: true;
                         }
                         field {
                             name: ("Server Plan")
-                            value: (format!("{}", server.server_plan.unwrap_or_else(|| String::from("Unknown"))))
+                            value: (plan_label(&catalog, server.server_plan.as_ref()))
                             inline: true;
                         }
                         field {
                             name: ("Active Server Plan")
-                            value: (format!("{}", server.active_server_plan.unwrap_or_else(|| String::from("Unknown"))))
+                            value: (plan_label(&catalog, server.active_server_plan.as_ref()))
                             inline: true;
                         }
                         field {
                             name: ("Server Version Type")
-                            value: (format!("{}", server.server_version_type.unwrap_or_else(|| String::from("Unknown"))))
+                            value: (server.server_version_type.as_ref().map_or_else(|| String::from("Unknown"), |version| version.to_string()))
                             inline: true;
                         }
                         field {
                             name: ("Player Count")
-                            value: (format!("{}", server.player_count.unwrap_or(0.0)))
+                            value: (format!("{}", server.player_count))
                             inline: true;
                         }
                         field {
                             name: ("Max Players")
-                            value: (format!("{}", server.max_players.unwrap_or(0.0)))
+                            value: (format!("{}", server.max_players))
+                            inline: true;
+                        }
+                        field {
+                            name: ("Created")
+                            value: (format_date(server.creation))
                             inline: true;
                         }
                         field {
                             name: ("Last Online")
-                            value: (format!("{}", date))
+                            value: (format_date(server.last_online))
                             inline: true;
                         }
                         field {
                             name: ("Categories")
-                            value: (format!("[{}]", server.categories.unwrap_or_default().join(", ")))
+                            value: (format!("[{}]", category_labels(&catalog, &server.categories)))
                             inline: true;
                         }
                         field {
                             name: ("Inherited Categories")
-                            value: (format!("[{}]", server.inherited_categories.unwrap_or_default().join(", ")))
+                            value: (format!("[{}]", category_labels(&catalog, &server.inherited_categories)))
                             inline: true;
                         }
                         field {
                             name: ("MOTD")
-                            value: (format!("{}", server.motd.unwrap_or_else(|| String::from("N/A"))))
+                            value: (server.motd.clone().unwrap_or_else(|| String::from("N/A")))
                             inline: false;
                         }
                         color: (Color::BLITZ_BLUE)
                     });
-                    if server.proxy.unwrap_or(false) {
+                    if server.proxy {
                         response_embed.field(
                             "Connected Servers",
-                            format!(
-                                "[{}]",
-                                server.connected_servers.unwrap_or_default().join(", ")
-                            ),
+                            format!("[{}]", server.connected_servers.join(", ")),
                             false,
                         );
                     }
                     response_embed
                 }
                 Err(err) => {
+                    let description = match &err {
+                        MinehutError::NotFound { .. } => {
+                            // only online servers can be suggested, the search runs over the list
+                            let suggestions = minehut_api::search::search_servers(server_name, 3)
+                                .await
+                                .unwrap_or_else(|err| {
+                                    log::warn!("Failed to search for {server_name}: {err:?}");
+                                    Vec::new()
+                                })
+                                .iter()
+                                .map(|candidate| format!("`{}`", candidate.name()))
+                                .collect::<Vec<_>>();
+                            if suggestions.is_empty() {
+                                format!("**Server {server_name} does not exist.**")
+                            } else {
+                                format!(
+                                    "**Server {server_name} does not exist.** Did you mean {}?",
+                                    suggestions.join(", ")
+                                )
+                            }
+                        }
+                        err => format!(
+                            "**Failed to resolve server {server_name}.** {}",
+                            api_error_message(err)
+                        ),
+                    };
                     embed!(response_embed {
                         author {
                             name: ("Click here for error information")
                             icon: (me.avatar_url().as_ref().unwrap())
-                            url: (format!("https://{}/server/{}?byName=true", std::env::var("MINEHUT_URL").unwrap_or_else(|_| "api.dev.minehut.com".to_string()), server_name))
+                            url: (server_url(server_name))
                         }
-                        description: (format!("**Failed to resolve server {server_name}.**"))
+                        description: (description)
                         color: (Color::DARK_RED)
                     });
-                    log::warn!("Potential error resolving servers: {err:?}");
+                    if !matches!(err, MinehutError::NotFound { .. }) {
+                        log::warn!("Error resolving server {server_name}: {err:?}");
+                    }
                     response_embed
                 }
             };
 
-            ack_embed(&ctx, &interaction, ack).await
+            edit_embed(&ctx, &interaction, ack).await
         } else {
             embed!(failure {
                 author {
@@ -249,6 +509,199 @@ pub fn server_command_handler(
     })
 }
 
+/// Who may run `/manage`, it starts and stops servers and rewrites their MOTD.
+const MANAGE_PERMISSIONS: Permissions = Permissions::MANAGE_GUILD;
+
+pub fn manage_command_handler(
+    ctx: serenity::client::Context,
+    interaction: ApplicationCommandInteraction,
+) -> ApplicationCommandFuture {
+    Box::pin(async move {
+        let me = ctx.cache.current_user();
+        let failure = |description: String| {
+            embed!(failure {
+                author {
+                    name: (&me.name)
+                    icon: (me.avatar_url().as_ref().unwrap())
+                }
+                description: (description)
+                color: (Color::DARK_RED)
+            });
+            failure
+        };
+
+        // Discord only hides the command from other members, guilds can override that
+        let permitted = interaction
+            .member
+            .as_ref()
+            .and_then(|member| member.permissions)
+            .is_some_and(|permissions| permissions.contains(MANAGE_PERMISSIONS));
+        if !permitted {
+            return ack_embed(
+                &ctx,
+                &interaction,
+                failure(String::from(
+                    "**Managing servers needs the Manage Server permission.**",
+                )),
+            )
+            .await;
+        }
+
+        let client = ctx
+            .data
+            .read()
+            .await
+            .get::<crate::ManagedClientKey>()
+            .cloned();
+        let Some(client) = client else {
+            return ack_embed(
+                &ctx,
+                &interaction,
+                failure(String::from(
+                    "**Managing servers needs `api_login` to be configured.**",
+                )),
+            )
+            .await;
+        };
+        let Some(server_name) = string_option(&interaction, "server") else {
+            return ack_embed(
+                &ctx,
+                &interaction,
+                failure(String::from("**Failed to resolve server value.**")),
+            )
+            .await;
+        };
+        let action = string_option(&interaction, "action").map(str::to_string);
+        let motd = string_option(&interaction, "motd").map(str::to_string);
+        let visible = bool_option(&interaction, "visible");
+        if action.is_none() && motd.is_none() && visible.is_none() {
+            return ack_embed(
+                &ctx,
+                &interaction,
+                failure(String::from(
+                    "**Nothing to change, pick an action, MOTD or visibility.**",
+                )),
+            )
+            .await;
+        }
+
+        // the lookup, the changes and fetching the server before and after take several calls
+        ack_deferred(&ctx, &interaction).await?;
+
+        let server = match client.get_server_by_name(server_name).await {
+            Ok(server) => client.owned_server(server.id),
+            Err(err) => {
+                return edit_embed(
+                    &ctx,
+                    &interaction,
+                    failure(format!(
+                        "**Failed to resolve server {server_name}.** {}",
+                        api_error_message(&err)
+                    )),
+                )
+                .await;
+            }
+        };
+
+        let change = server
+            .change(|server| async move {
+                match action.as_deref() {
+                    Some("start") => server.start().await?,
+                    Some("stop") => server.stop().await?,
+                    Some("restart") => server.restart().await?,
+                    Some("hibernate") => server.hibernate().await?,
+                    _ => {}
+                }
+                if let Some(motd) = &motd {
+                    server.set_motd(motd).await?;
+                }
+                if let Some(visible) = visible {
+                    server.set_visibility(visible).await?;
+                }
+                Ok(())
+            })
+            .await;
+        let change = match change {
+            Ok(change) => change,
+            Err(err) => {
+                log::warn!("Failed to manage server {server_name}: {err:?}");
+                return edit_embed(
+                    &ctx,
+                    &interaction,
+                    failure(format!(
+                        "**Failed to manage server {server_name}.** {}",
+                        api_error_message(&err)
+                    )),
+                )
+                .await;
+            }
+        };
+        let status = server.status().await.ok().and_then(|status| status.status);
+
+        embed!(response_embed {
+            author {
+                name: (&me.name)
+                icon: (me.avatar_url().as_ref().unwrap())
+            }
+            description: (format!("**Updated server {server_name}[{}]**", server.id()))
+            field {
+                name: ("Status")
+                value: (status.map_or_else(|| String::from("Unknown"), |status| status.to_string()))
+                inline: false;
+            }
+            color: (Color::BLITZ_BLUE)
+        });
+        // embeds allow 25 fields, one is taken by the status
+        // field names are capped at 256 characters and values at 1024, shared by both sides
+        for (field, before, after) in change.differences().into_iter().take(24) {
+            let field = field.chars().take(256).collect::<String>();
+            let before = before.to_string().chars().take(500).collect::<String>();
+            let after = after.to_string().chars().take(500).collect::<String>();
+            response_embed.field(field, format!("`{before}` -> `{after}`"), true);
+        }
+
+        edit_embed(&ctx, &interaction, response_embed).await
+    })
+}
+
+pub fn api_health_command_handler(
+    ctx: serenity::client::Context,
+    interaction: ApplicationCommandInteraction,
+) -> ApplicationCommandFuture {
+    Box::pin(async move {
+        let me = ctx.cache.current_user();
+        let metrics = ctx
+            .data
+            .read()
+            .await
+            .get::<crate::ApiMetricsKey>()
+            .cloned()
+            .context("API metrics were not configured.")?;
+
+        let mut endpoints = metrics.snapshot().into_iter().collect::<Vec<_>>();
+        endpoints.sort_by(|(_, a), (_, b)| b.calls.cmp(&a.calls));
+
+        embed!(response_embed {
+            author {
+                name: (&me.name)
+                icon: (me.avatar_url().as_ref().unwrap())
+            }
+            description: ("**Minehut API health since the bot started.**")
+            field {
+                name: ("Total")
+                value: (metrics_summary(&metrics.total()))
+                inline: false;
+            }
+            color: (Color::BLITZ_BLUE)
+        });
+        for (endpoint, metrics) in endpoints.iter().take(24) {
+            response_embed.field(endpoint, metrics_summary(metrics), false);
+        }
+
+        ack_embed(&ctx, &interaction, response_embed).await
+    })
+}
+
 pub async fn configure(
     ctx: &serenity::client::Context,
     command_handles: &mut crate::event_handler::CommandHandlers,
@@ -264,6 +717,23 @@ pub async fn configure(
                     .kind(CommandOptionType::String)
                     .required(true)
             })
+            .create_option(|option| {
+                option
+                    .name("method")
+                    .description("HTTP method to call with, defaults to GET.")
+                    .kind(CommandOptionType::String)
+                    .add_string_choice("GET", "GET")
+                    .add_string_choice("POST", "POST")
+                    .add_string_choice("PUT", "PUT")
+                    .add_string_choice("PATCH", "PATCH")
+                    .add_string_choice("DELETE", "DELETE")
+            })
+            .create_option(|option| {
+                option
+                    .name("body")
+                    .description("JSON body to send with the call.")
+                    .kind(CommandOptionType::String)
+            })
     })
     .await?;
     command_handles.register_handle("raw_call", raw_call_command_handle);
@@ -291,5 +761,52 @@ pub async fn configure(
     .await?;
     command_handles.register_handle("server", server_command_handler);
 
+    Command::create_global_application_command(&ctx.http, |command| {
+        command
+            .name("manage")
+            .description("Changes a server owned by the configured Minehut account.")
+            .default_member_permissions(MANAGE_PERMISSIONS)
+            .dm_permission(false)
+            .create_option(|option| {
+                option
+                    .name("server")
+                    .description("A server to manage.")
+                    .kind(CommandOptionType::String)
+                    .required(true)
+            })
+            .create_option(|option| {
+                option
+                    .name("action")
+                    .description("Lifecycle action to run.")
+                    .kind(CommandOptionType::String)
+                    .add_string_choice("Start", "start")
+                    .add_string_choice("Stop", "stop")
+                    .add_string_choice("Restart", "restart")
+                    .add_string_choice("Hibernate", "hibernate")
+            })
+            .create_option(|option| {
+                option
+                    .name("motd")
+                    .description("New MOTD for the server.")
+                    .kind(CommandOptionType::String)
+            })
+            .create_option(|option| {
+                option
+                    .name("visible")
+                    .description("Whether the server shows in the server list.")
+                    .kind(CommandOptionType::Boolean)
+            })
+    })
+    .await?;
+    command_handles.register_handle("manage", manage_command_handler);
+
+    Command::create_global_application_command(&ctx.http, |command| {
+        command
+            .name("api_health")
+            .description("Shows latency and error rates of the bot's calls to Minehut's API.")
+    })
+    .await?;
+    command_handles.register_handle("api_health", api_health_command_handler);
+
     Ok(())
 }
@@ -2,11 +2,28 @@ mod collectors;
 mod commands;
 mod event_handler;
 mod minecraft_bot;
+mod minecraft_srv;
+mod minecraft_status;
 mod reporters;
 
 use crate::event_handler::{CommandHandlerKey, CommandHandlers};
+use crate::minecraft_bot::CancelToken;
 use anyhow::Context;
+use minehut_api::cassette::Cassette;
+use minehut_api::prelude::{
+    ApiMetrics, CacheConfig, Credentials, DriftMode, DriftReport, Environment, MinehutClient,
+    MinehutClientBuilder, RetryPolicy, Session,
+};
 use serenity::prelude::*;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+static SHUTDOWN: OnceLock<CancelToken> = OnceLock::new();
+
+/// Cancelled once the bot starts shutting down, stopping any status request still running.
+pub fn shutdown_token() -> CancelToken {
+    SHUTDOWN.get_or_init(CancelToken::new).clone()
+}
 
 #[derive(serde_derive::Deserialize, Debug, Copy, Clone)]
 pub enum LevelFilter {
@@ -31,6 +48,28 @@ impl LevelFilter {
     }
 }
 
+#[derive(serde_derive::Deserialize, Debug, Copy, Clone)]
+pub enum CassetteMode {
+    RECORD,
+    REPLAY,
+}
+
+#[derive(Debug, serde_derive::Deserialize)]
+struct CassetteConfiguration {
+    mode: CassetteMode,
+    path: String,
+}
+
+impl CassetteConfiguration {
+    fn to_cassette(&self) -> anyhow::Result<Cassette> {
+        match self.mode {
+            CassetteMode::RECORD => Cassette::record(&self.path),
+            CassetteMode::REPLAY => Cassette::replay(&self.path),
+        }
+        .with_context(|| format!("Failed to open API cassette {}.", self.path))
+    }
+}
+
 #[derive(Debug, serde_derive::Deserialize)]
 struct Configuration {
     token: String,
@@ -39,15 +78,89 @@ struct Configuration {
     // for builtin_network_stats_monitor
     builtin_network_stats_monitor_channel: u64,
     builtin_network_stats_monitor_message: u64,
+    // appends every network stats sample to this file in minehut_api's snapshot format
+    #[serde(default)]
+    builtin_network_stats_monitor_archive: Option<String>,
     // for builtin_minecraft_stats_monitor
     builtin_minecraft_stats_monitor_channel: u64,
     builtin_minecraft_stats_monitor_message: u64,
+    // which Minehut API to call and link to, ex: "prod", "dev" or "http://127.0.0.1:8080"
+    // falls back to the `MINEHUT_URL` environment variable, then the prod api, unlike
+    // `Environment::from_env` which falls back to the dev api
+    #[serde(default)]
+    api_environment: Option<Environment>,
+    // shared by every call to Minehut's API
+    #[serde(default)]
+    api_requests_per_second: Option<f64>,
+    #[serde(default)]
+    api_retry_attempts: Option<u32>,
+    // posts Minehut API shape changes to the tools channel instead of only logging them
+    #[serde(default)]
+    api_drift_alerts: bool,
+    // records Minehut API calls to, or replays them from, a cassette file
+    #[serde(default)]
+    api_cassette: Option<CassetteConfiguration>,
+    // reuses identical Minehut API responses for this many seconds
+    #[serde(default)]
+    api_cache_ttl_secs: Option<u64>,
+    // logs in to Minehut for /manage, which only works on servers this account owns
+    #[serde(default)]
+    api_login: Option<Credentials>,
 }
 
 impl Configuration {
     pub fn tools_channel(&self) -> u64 {
         self.tools_channel
     }
+
+    fn minehut_client(
+        &self,
+        drift_sender: flume::Sender<DriftReport>,
+        metrics: Arc<ApiMetrics>,
+    ) -> anyhow::Result<MinehutClient> {
+        let environment = self
+            .api_environment
+            .clone()
+            .or_else(|| std::env::var("MINEHUT_URL").ok().map(Environment::from))
+            .unwrap_or(Environment::Production);
+        let mut builder = MinehutClientBuilder::default()
+            .environment(environment)
+            .metrics(metrics);
+        if self.api_drift_alerts {
+            builder = builder.drift_mode(DriftMode::Callback(Arc::new(move |report| {
+                if let Err(err) = drift_sender.send(report.clone()) {
+                    log::error!("Failed to queue API drift report: {err:?}");
+                }
+            })));
+        } else {
+            builder = builder.drift_mode(DriftMode::Log);
+        }
+        if let Some(per_second) = self.api_requests_per_second {
+            if per_second.is_nan() || per_second <= 0.0 {
+                anyhow::bail!("api_requests_per_second must be above 0, got {per_second}.");
+            }
+            builder = builder.rate_limit(per_second, (per_second.ceil() as u32).max(1));
+        }
+        if let Some(attempts) = self.api_retry_attempts {
+            builder = builder.retry_policy(RetryPolicy::default().max_attempts(attempts));
+        }
+        if let Some(cassette) = &self.api_cassette {
+            builder = builder.cassette(cassette.to_cassette()?);
+        }
+        // the catalogs rarely change, so they're kept for at least an hour even when nothing else
+        // is cached, every /server call looks them up
+        let ttl = Duration::from_secs(self.api_cache_ttl_secs.unwrap_or(0));
+        let catalog_ttl = ttl.max(Duration::from_secs(60 * 60));
+        builder
+            .cache(
+                CacheConfig::new(ttl)
+                    .ttl("/plans", catalog_ttl)
+                    .ttl("/categories", catalog_ttl)
+                    .ttl("/icons", catalog_ttl),
+            )
+            .build()
+            .context("Failed to build the Minehut API client.")
+    }
 }
 
 struct ConfigurationTypeKey;
@@ -56,6 +169,20 @@ impl TypeMapKey for ConfigurationTypeKey {
     type Value = Configuration;
 }
 
+/// Latency and status counts for every Minehut API call the bot makes.
+struct ApiMetricsKey;
+
+impl TypeMapKey for ApiMetricsKey {
+    type Value = Arc<ApiMetrics>;
+}
+
+/// The Minehut client logged in as `api_login`, only present when it is configured.
+struct ManagedClientKey;
+
+impl TypeMapKey for ManagedClientKey {
+    type Value = MinehutClient;
+}
+
 fn read_config() -> anyhow::Result<Configuration> {
     let file = std::fs::File::open("./etc/config.json")?;
     let reader = std::io::BufReader::new(file);
@@ -82,16 +209,42 @@ async fn main() -> anyhow::Result<()> {
 
     log::debug!("Read discord tracker config as: {config:#?}");
 
+    let (drift_sender, drift_receiver) = flume::unbounded();
+    let api_metrics = Arc::new(ApiMetrics::new());
+    let minehut_client = config.minehut_client(drift_sender, api_metrics.clone())?;
+    if minehut_api::client::init_default_client(minehut_client).is_err() {
+        log::warn!("Minehut API client was used before it could be configured.");
+    }
+
     let mut client = Client::builder(&config.token, GatewayIntents::all())
         .event_handler(event_handler::Handler)
         .await
         .expect("Error occurred creating client.");
 
     let mut data_write_lock = client.data.write().await;
+    if let Some(credentials) = &config.api_login {
+        let managed_client = minehut_api::client::default_client()
+            .with_session(Session::with_credentials(credentials.clone()));
+        data_write_lock.insert::<ManagedClientKey>(managed_client);
+    }
     data_write_lock.insert::<ConfigurationTypeKey>(config);
+    data_write_lock.insert::<ApiMetricsKey>(api_metrics);
     data_write_lock.insert::<CommandHandlerKey>(CommandHandlers::default());
+    data_write_lock.insert::<collectors::DriftReportKey>(drift_receiver);
     drop(data_write_lock);
 
+    let shard_manager = client.shard_manager.clone();
+    tokio::spawn(async move {
+        match tokio::signal::ctrl_c().await {
+            Ok(()) => {
+                log::info!("Shutting down.");
+                shutdown_token().cancel();
+                shard_manager.lock().await.shutdown_all().await;
+            }
+            Err(err) => log::error!("Failed to listen for the shutdown signal: {err:?}"),
+        }
+    });
+
     if let Err(err) = client.start().await {
         log::error!("Client error: {:?}", err);
     }
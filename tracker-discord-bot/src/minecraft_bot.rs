@@ -1,14 +1,17 @@
+use crate::minecraft_srv::{resolve_address, SrvResolver};
 use mc_protocol::ext::write_packet;
 use mc_protocol::packets::client_bound::status::{JSONResponse, Pong, StatusResponse};
 use mc_protocol::packets::packet_async::ProtocolSheet;
 use mc_protocol::packets::server_bound::handshaking::{Handshake, NextState, ServerAddress};
 use mc_protocol::packets::server_bound::status::{Ping, StatusRequest};
 use mc_protocol::{wrap_async_packet_handle, MinecraftPacketBuffer, ProtocolVersion};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::net::tcp::OwnedWriteHalf;
 use tokio::net::TcpStream;
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
+use tokio::time::timeout;
 
 pub fn get_system_time_as_millis() -> u128 {
     SystemTime::now()
@@ -17,10 +20,154 @@ pub fn get_system_time_as_millis() -> u128 {
         .as_millis()
 }
 
+/// How long each part of a status request may take.
+#[derive(Debug, Copy, Clone)]
+pub struct StatusTimeouts {
+    /// For the TCP connection to open.
+    pub connect: Duration,
+    /// For each packet from the server, counted from the end of the previous one.
+    pub read: Duration,
+    /// For the whole request, from connecting to the pong.
+    pub overall: Duration,
+}
+
+impl Default for StatusTimeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(5),
+            read: Duration::from_secs(5),
+            overall: Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StatusPhase {
+    Connect,
+    Read,
+    Overall,
+}
+
+impl std::fmt::Display for StatusPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StatusPhase::Connect => "connect",
+            StatusPhase::Read => "read",
+            StatusPhase::Overall => "overall",
+        })
+    }
+}
+
+/// Why a status request gave up, found with `err.downcast_ref::<StatusError>()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StatusError {
+    TimedOut { phase: StatusPhase, after: Duration },
+    Cancelled,
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatusError::TimedOut { phase, after } => {
+                write!(f, "Status request hit its {phase} timeout after {after:?}.")
+            }
+            StatusError::Cancelled => f.write_str("Status request was cancelled."),
+        }
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// Stops every status request it's passed to, clones share the same cancellation.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelTokenInner>,
+}
+
+#[derive(Debug, Default)]
+struct CancelTokenInner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once [`CancelToken::cancel`] has been called.
+    pub async fn cancelled(&self) {
+        loop {
+            // registered before checking so a cancel in between isn't missed
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// [`request_status_with`] using the default timeouts, no cancellation and no SRV lookup.
 pub async fn request_status<S: ToString>(
     address: S,
     port: u16,
     protocol_version: ProtocolVersion,
+) -> anyhow::Result<(StatusResponse, u128)> {
+    request_status_with(
+        address,
+        Some(port),
+        protocol_version,
+        StatusTimeouts::default(),
+        &CancelToken::new(),
+        None,
+    )
+    .await
+}
+
+/// Queries a server's status and ping, giving up with a [`StatusError`] once a timeout passes or
+/// `cancel` is cancelled.
+///
+/// With `srv` and no `port`, the connection goes wherever `address`'s `_minecraft._tcp` record
+/// points, the handshake still names `address` so proxies route it like a player's join. The
+/// lookup counts towards the connect timeout.
+pub async fn request_status_with<S: ToString>(
+    address: S,
+    port: Option<u16>,
+    protocol_version: ProtocolVersion,
+    timeouts: StatusTimeouts,
+    cancel: &CancelToken,
+    srv: Option<&dyn SrvResolver>,
+) -> anyhow::Result<(StatusResponse, u128)> {
+    let host = address.to_string().trim().trim_end_matches('.').to_string();
+    let exchange = exchange_status(host, port, protocol_version, timeouts, srv);
+    tokio::select! {
+        _ = cancel.cancelled() => Err(StatusError::Cancelled.into()),
+        result = timeout(timeouts.overall, exchange) => result.unwrap_or_else(|_| {
+            Err(StatusError::TimedOut {
+                phase: StatusPhase::Overall,
+                after: timeouts.overall,
+            }
+            .into())
+        }),
+    }
+}
+
+async fn exchange_status(
+    host: String,
+    port: Option<u16>,
+    protocol_version: ProtocolVersion,
+    timeouts: StatusTimeouts,
+    srv: Option<&dyn SrvResolver>,
 ) -> anyhow::Result<(StatusResponse, u128)> {
     struct Context {
         response: Option<StatusResponse>,
@@ -50,15 +197,27 @@ pub async fn request_status<S: ToString>(
     sheet.register_packet_handle::<Pong>(handle_pong_response);
 
     let mut buffer = MinecraftPacketBuffer::new();
-    let stream = TcpStream::connect(format!("{}:{}", address.to_string(), port)).await?;
+    let connect = async {
+        let address = resolve_address(srv, &host, port).await;
+        let (connect_host, connect_port) = &address.connect;
+        let stream = TcpStream::connect((connect_host.as_str(), *connect_port)).await?;
+        anyhow::Ok((stream, address.handshake))
+    };
+    let (stream, (handshake_host, handshake_port)) =
+        timeout(timeouts.connect, connect)
+            .await
+            .map_err(|_| StatusError::TimedOut {
+                phase: StatusPhase::Connect,
+                after: timeouts.connect,
+            })??;
 
     let (mut read_half, mut write_half) = stream.into_split();
 
     write_packet(
         Handshake {
             protocol_version: protocol_version.to_spec().0.into(),
-            server_address: ServerAddress::from(address.to_string()),
-            server_port: port,
+            server_address: ServerAddress::from(handshake_host),
+            server_port: handshake_port,
             next_state: (1i32.into(), NextState::Status {}),
         },
         ProtocolVersion::Handshake,
@@ -76,7 +235,13 @@ pub async fn request_status<S: ToString>(
     let locked_sheet = Arc::new(RwLock::new(sheet));
     let locked_context = Arc::new(RwLock::new(context));
 
-    while let Ok(pass_back) = buffer.read_to_next_packet(read_half).await {
+    while let Ok(pass_back) = timeout(timeouts.read, buffer.read_to_next_packet(read_half))
+        .await
+        .map_err(|_| StatusError::TimedOut {
+            phase: StatusPhase::Read,
+            after: timeouts.read,
+        })?
+    {
         read_half = pass_back;
         ProtocolSheet::call_generic(
             Arc::clone(&locked_sheet),
@@ -87,16 +252,152 @@ pub async fn request_status<S: ToString>(
 
         let read_context = RwLock::read(&locked_context).await;
         if let (Some(response), Some(pong)) = (&read_context.response, &read_context.pong) {
+            // servers echo the payload back, but nothing stops one sending a later time
+            let sent_at = u128::try_from(pong.payload).unwrap_or_default();
             return Ok((
                 StatusResponse {
                     json_response: JSONResponse::from(
                         String::from(&response.json_response)
                     ),
                 },
-                get_system_time_as_millis() - (pong.payload as u128),
+                get_system_time_as_millis().saturating_sub(sent_at),
             ));
         }
     }
 
     anyhow::bail!("Failed to query status.")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::futures::future::{pending, BoxFuture};
+    use tokio::net::TcpListener;
+
+    /// Never answers, so every lookup runs into the connect timeout.
+    struct PendingSrvResolver;
+
+    impl SrvResolver for PendingSrvResolver {
+        fn lookup_srv<'a>(
+            &'a self,
+            _name: &'a str,
+        ) -> BoxFuture<'a, anyhow::Result<Vec<crate::minecraft_srv::SrvRecord>>> {
+            Box::pin(pending())
+        }
+    }
+
+    fn timeouts(connect: u64, read: u64, overall: u64) -> StatusTimeouts {
+        StatusTimeouts {
+            connect: Duration::from_millis(connect),
+            read: Duration::from_millis(read),
+            overall: Duration::from_millis(overall),
+        }
+    }
+
+    async fn status_error(
+        address: &str,
+        port: Option<u16>,
+        timeouts: StatusTimeouts,
+        cancel: &CancelToken,
+        srv: Option<&dyn SrvResolver>,
+    ) -> StatusError {
+        let err = request_status_with(
+            address,
+            port,
+            ProtocolVersion::V118R2,
+            timeouts,
+            cancel,
+            srv,
+        )
+        .await
+        .expect_err("status request should have given up");
+        *err.downcast_ref::<StatusError>()
+            .unwrap_or_else(|| panic!("expected a StatusError, got {err:?}"))
+    }
+
+    /// Accepts connections, then never writes a byte.
+    async fn silent_server() -> (TcpListener, u16) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        (listener, port)
+    }
+
+    #[tokio::test]
+    async fn connect_timeout_covers_the_srv_lookup() {
+        let err = status_error(
+            "mc.example.test",
+            None,
+            timeouts(100, 5_000, 5_000),
+            &CancelToken::new(),
+            Some(&PendingSrvResolver),
+        )
+        .await;
+        assert_eq!(
+            err,
+            StatusError::TimedOut {
+                phase: StatusPhase::Connect,
+                after: Duration::from_millis(100),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn read_timeout_stops_waiting_on_a_silent_server() {
+        let (_listener, port) = silent_server().await;
+        let err = status_error(
+            "127.0.0.1",
+            Some(port),
+            timeouts(5_000, 100, 5_000),
+            &CancelToken::new(),
+            None,
+        )
+        .await;
+        assert_eq!(
+            err,
+            StatusError::TimedOut {
+                phase: StatusPhase::Read,
+                after: Duration::from_millis(100),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn overall_timeout_wins_over_slower_phases() {
+        let (_listener, port) = silent_server().await;
+        let err = status_error(
+            "127.0.0.1",
+            Some(port),
+            timeouts(5_000, 5_000, 100),
+            &CancelToken::new(),
+            None,
+        )
+        .await;
+        assert_eq!(
+            err,
+            StatusError::TimedOut {
+                phase: StatusPhase::Overall,
+                after: Duration::from_millis(100),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn cancelling_stops_a_running_request() {
+        let (_listener, port) = silent_server().await;
+        let cancel = CancelToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            canceller.cancel();
+        });
+        let err = status_error(
+            "127.0.0.1",
+            Some(port),
+            timeouts(5_000, 5_000, 5_000),
+            &cancel,
+            None,
+        )
+        .await;
+        assert_eq!(err, StatusError::Cancelled);
+    }
+}
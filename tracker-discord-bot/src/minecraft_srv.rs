@@ -0,0 +1,212 @@
+//! Finding where a Minecraft server really lives through its `_minecraft._tcp` SRV record.
+
+use hickory_resolver::error::ResolveErrorKind;
+use hickory_resolver::TokioAsyncResolver;
+use serenity::futures::future::BoxFuture;
+use std::net::IpAddr;
+
+/// The port players connect to when they don't name one.
+pub const DEFAULT_PORT: u16 = 25565;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrvRecord {
+    pub priority: u16,
+    pub weight: u16,
+    pub port: u16,
+    /// The host to connect to, without a trailing dot.
+    pub target: String,
+}
+
+/// Looks up SRV records, swapped out in tests where real DNS isn't wanted.
+pub trait SrvResolver: Send + Sync {
+    /// Every record for `name`, ex: `_minecraft._tcp.minehut.com`, empty if it has none.
+    fn lookup_srv<'a>(&'a self, name: &'a str) -> BoxFuture<'a, anyhow::Result<Vec<SrvRecord>>>;
+}
+
+/// Resolves through the system's DNS configuration.
+pub struct SystemSrvResolver {
+    resolver: TokioAsyncResolver,
+}
+
+impl SystemSrvResolver {
+    pub fn from_system_conf() -> anyhow::Result<Self> {
+        Ok(Self {
+            resolver: TokioAsyncResolver::tokio_from_system_conf()?,
+        })
+    }
+}
+
+impl SrvResolver for SystemSrvResolver {
+    fn lookup_srv<'a>(&'a self, name: &'a str) -> BoxFuture<'a, anyhow::Result<Vec<SrvRecord>>> {
+        Box::pin(async move {
+            match self.resolver.srv_lookup(name).await {
+                Ok(lookup) => Ok(lookup
+                    .iter()
+                    .map(|srv| SrvRecord {
+                        priority: srv.priority(),
+                        weight: srv.weight(),
+                        port: srv.port(),
+                        target: srv.target().to_utf8().trim_end_matches('.').to_string(),
+                    })
+                    .collect()),
+                Err(err) if matches!(err.kind(), ResolveErrorKind::NoRecordsFound { .. }) => {
+                    Ok(Vec::new())
+                }
+                Err(err) => Err(err.into()),
+            }
+        })
+    }
+}
+
+/// Where a status request for an address goes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedAddress {
+    /// The host and port the connection is opened to.
+    pub connect: (String, u16),
+    /// The host and port named in the handshake, always the address as given so proxies route
+    /// the request like a player's join.
+    pub handshake: (String, u16),
+}
+
+/// Resolves `host` like the vanilla client: without a `port`, the connection goes wherever its
+/// SRV record points. A missing record or failed lookup falls back to `host` on
+/// [`DEFAULT_PORT`], and IP literals and explicit ports never look anything up.
+pub async fn resolve_address(
+    resolver: Option<&dyn SrvResolver>,
+    host: &str,
+    port: Option<u16>,
+) -> ResolvedAddress {
+    let handshake = (host.to_string(), port.unwrap_or(DEFAULT_PORT));
+    let connect = match (resolver, port) {
+        (Some(resolver), None) if host.parse::<IpAddr>().is_err() => {
+            lookup_connect_address(resolver, host)
+                .await
+                .unwrap_or_else(|| handshake.clone())
+        }
+        _ => handshake.clone(),
+    };
+    ResolvedAddress { connect, handshake }
+}
+
+/// Where `host`'s SRV record points, `None` if it has none or the lookup failed.
+async fn lookup_connect_address(resolver: &dyn SrvResolver, host: &str) -> Option<(String, u16)> {
+    let name = format!("_minecraft._tcp.{host}");
+    match resolver.lookup_srv(&name).await {
+        Ok(records) => {
+            // the lowest priority wins, heavier records first among equals
+            let record = records
+                .into_iter()
+                .filter(|record| !record.target.is_empty())
+                .min_by_key(|record| (record.priority, u16::MAX - record.weight))?;
+            log::debug!("{name} points at {}:{}", record.target, record.port);
+            Some((record.target, record.port))
+        }
+        Err(err) => {
+            log::warn!("Failed to look up {name}, connecting directly: {err:?}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Answers from a fixed set of records.
+    #[derive(Default)]
+    struct StaticSrvResolver {
+        records: HashMap<String, Vec<SrvRecord>>,
+    }
+
+    impl StaticSrvResolver {
+        fn with_record(mut self, name: &str, priority: u16, weight: u16, target: &str) -> Self {
+            self.records
+                .entry(name.to_string())
+                .or_default()
+                .push(SrvRecord {
+                    priority,
+                    weight,
+                    port: 25000 + priority * 10 + weight,
+                    target: target.to_string(),
+                });
+            self
+        }
+    }
+
+    impl SrvResolver for StaticSrvResolver {
+        fn lookup_srv<'a>(
+            &'a self,
+            name: &'a str,
+        ) -> BoxFuture<'a, anyhow::Result<Vec<SrvRecord>>> {
+            let records = self.records.get(name).cloned().unwrap_or_default();
+            Box::pin(async move { Ok(records) })
+        }
+    }
+
+    struct FailingSrvResolver;
+
+    impl SrvResolver for FailingSrvResolver {
+        fn lookup_srv<'a>(
+            &'a self,
+            _name: &'a str,
+        ) -> BoxFuture<'a, anyhow::Result<Vec<SrvRecord>>> {
+            Box::pin(async { anyhow::bail!("SERVFAIL") })
+        }
+    }
+
+    const NAME: &str = "_minecraft._tcp.minehut.test";
+
+    fn address(host: &str, port: u16) -> (String, u16) {
+        (host.to_string(), port)
+    }
+
+    #[tokio::test]
+    async fn lowest_priority_then_highest_weight_wins() {
+        let resolver = StaticSrvResolver::default()
+            .with_record(NAME, 20, 90, "backup.minehut.test")
+            .with_record(NAME, 10, 1, "light.minehut.test")
+            .with_record(NAME, 10, 5, "heavy.minehut.test")
+            .with_record(NAME, 10, 3, "");
+        let resolved = resolve_address(Some(&resolver), "minehut.test", None).await;
+        assert_eq!(resolved.connect, address("heavy.minehut.test", 25105));
+    }
+
+    #[tokio::test]
+    async fn handshakes_name_the_original_address() {
+        let resolver = StaticSrvResolver::default().with_record(NAME, 0, 0, "proxy.minehut.test");
+        let resolved = resolve_address(Some(&resolver), "minehut.test", None).await;
+        assert_eq!(resolved.connect, address("proxy.minehut.test", 25000));
+        assert_eq!(resolved.handshake, address("minehut.test", DEFAULT_PORT));
+    }
+
+    #[tokio::test]
+    async fn ip_literals_and_explicit_ports_skip_the_lookup() {
+        let resolver = StaticSrvResolver::default()
+            .with_record(NAME, 0, 0, "proxy.minehut.test")
+            .with_record("_minecraft._tcp.127.0.0.1", 0, 0, "proxy.minehut.test");
+
+        let resolved = resolve_address(Some(&resolver), "127.0.0.1", None).await;
+        assert_eq!(resolved.connect, address("127.0.0.1", DEFAULT_PORT));
+
+        let resolved = resolve_address(Some(&resolver), "minehut.test", Some(25570)).await;
+        assert_eq!(resolved.connect, address("minehut.test", 25570));
+        assert_eq!(resolved.handshake, resolved.connect);
+    }
+
+    #[tokio::test]
+    async fn missing_records_and_failed_lookups_connect_directly() {
+        let direct = address("minehut.test", DEFAULT_PORT);
+
+        let empty = StaticSrvResolver::default();
+        let resolved = resolve_address(Some(&empty), "minehut.test", None).await;
+        assert_eq!(resolved.connect, direct);
+
+        let resolved = resolve_address(Some(&FailingSrvResolver), "minehut.test", None).await;
+        assert_eq!(resolved.connect, direct);
+        assert_eq!(resolved.handshake, direct);
+
+        let resolved = resolve_address(None, "minehut.test", None).await;
+        assert_eq!(resolved.connect, direct);
+    }
+}
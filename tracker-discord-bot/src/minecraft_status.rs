@@ -0,0 +1,317 @@
+//! The JSON a Minecraft server answers a server list ping with.
+//!
+//! Proxies and modded servers often bend the vanilla format, so every field is optional and a
+//! malformed field falls back to its default instead of failing the whole response.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(serde_derive::Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ServerStatus {
+    #[serde(default, deserialize_with = "lenient")]
+    pub version: StatusVersion,
+    #[serde(default, deserialize_with = "lenient")]
+    pub players: StatusPlayers,
+    /// The MOTD.
+    #[serde(default)]
+    pub description: ChatComponent,
+    /// A `data:image/png;base64,` url of the server's 64x64 icon.
+    #[serde(default, deserialize_with = "lenient")]
+    pub favicon: Option<String>,
+    #[serde(default, rename = "enforcesSecureChat", deserialize_with = "lenient")]
+    pub enforces_secure_chat: Option<bool>,
+    #[serde(default, rename = "previewsChat", deserialize_with = "lenient")]
+    pub previews_chat: Option<bool>,
+    /// Fields outside the vanilla format, ex: Forge's `forgeData`.
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+impl ServerStatus {
+    pub fn parse(json: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// The base64 encoded PNG from the favicon's data url.
+    pub fn favicon_base64(&self) -> Option<String> {
+        let favicon = self.favicon.as_deref()?;
+        let (_, data) = favicon.split_once("base64,")?;
+        // some servers wrap the data like a PEM file
+        Some(data.chars().filter(|c| !c.is_whitespace()).collect())
+    }
+}
+
+#[derive(serde_derive::Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct StatusVersion {
+    /// Ex: `1.18.2`, proxies put anything here, ex: `Velocity 1.7.2-1.19`.
+    #[serde(default, deserialize_with = "lenient_string")]
+    pub name: String,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub protocol: i64,
+}
+
+#[derive(serde_derive::Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct StatusPlayers {
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub online: i64,
+    #[serde(default, deserialize_with = "lenient_number")]
+    pub max: i64,
+    /// Some of the online players, proxies often fill this with lines of text instead.
+    #[serde(default, deserialize_with = "lenient_sample")]
+    pub sample: Vec<PlayerSample>,
+}
+
+#[derive(serde_derive::Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PlayerSample {
+    #[serde(default, deserialize_with = "lenient_string")]
+    pub name: String,
+    /// The player's UUID, all zeros for samples which aren't players.
+    #[serde(default, deserialize_with = "lenient_string")]
+    pub id: String,
+}
+
+/// Formatted text, the MOTD is either a plain string, a component or an array of components.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChatComponent {
+    pub text: String,
+    /// A translation key used in place of `text`, ex: `multiplayer.status.cannot_connect`.
+    pub translate: Option<String>,
+    /// A color name like `gold`, or `#rrggbb`.
+    pub color: Option<String>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub obfuscated: Option<bool>,
+    /// Components after this one, inheriting its formatting.
+    pub extra: Vec<ChatComponent>,
+}
+
+impl ChatComponent {
+    /// The text of this component and its children, without `§` formatting codes.
+    pub fn plain_text(&self) -> String {
+        let mut text = String::new();
+        self.push_text(&mut text);
+        strip_formatting_codes(&text)
+    }
+
+    fn push_text(&self, out: &mut String) {
+        match &self.translate {
+            Some(key) if self.text.is_empty() => out.push_str(key),
+            _ => out.push_str(&self.text),
+        }
+        for child in &self.extra {
+            child.push_text(out);
+        }
+    }
+
+    fn from_value(value: Value) -> Self {
+        match value {
+            Value::Null => Self::default(),
+            Value::String(text) => Self {
+                text,
+                ..Self::default()
+            },
+            Value::Bool(_) | Value::Number(_) => Self {
+                text: value.to_string(),
+                ..Self::default()
+            },
+            // the first component is the parent of the rest
+            Value::Array(items) => {
+                let mut items = items.into_iter().map(ChatComponent::from_value);
+                let mut component = items.next().unwrap_or_default();
+                component.extra.extend(items);
+                component
+            }
+            Value::Object(mut fields) => {
+                let mut take_bool = |field: &str| fields.remove(field).and_then(|v| v.as_bool());
+                let (bold, italic, underlined, strikethrough, obfuscated) = (
+                    take_bool("bold"),
+                    take_bool("italic"),
+                    take_bool("underlined"),
+                    take_bool("strikethrough"),
+                    take_bool("obfuscated"),
+                );
+                let mut take_string = |field: &str| match fields.remove(field) {
+                    Some(Value::String(value)) => Some(value),
+                    Some(Value::Null) | None => None,
+                    Some(value) => Some(value.to_string()),
+                };
+                Self {
+                    text: take_string("text").unwrap_or_default(),
+                    translate: take_string("translate"),
+                    color: take_string("color"),
+                    bold,
+                    italic,
+                    underlined,
+                    strikethrough,
+                    obfuscated,
+                    extra: match fields.remove("extra") {
+                        Some(Value::Array(items)) => {
+                            items.into_iter().map(ChatComponent::from_value).collect()
+                        }
+                        Some(Value::Null) | None => Vec::new(),
+                        Some(value) => vec![ChatComponent::from_value(value)],
+                    },
+                }
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ChatComponent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(ChatComponent::from_value(Value::deserialize(deserializer)?))
+    }
+}
+
+/// Removes legacy `§` codes, ex: `§6Minehut` becomes `Minehut`.
+pub fn strip_formatting_codes(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+fn lenient<'de, D: Deserializer<'de>, T: DeserializeOwned + Default>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    Ok(serde_json::from_value(Value::deserialize(deserializer)?).unwrap_or_default())
+}
+
+fn lenient_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(value) => value,
+        Value::Null => String::new(),
+        value => value.to_string(),
+    })
+}
+
+/// Numbers sent as strings or floats are accepted too.
+fn lenient_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Number(number) => number
+            .as_i64()
+            .or_else(|| number.as_f64().map(|value| value as i64))
+            .unwrap_or_default(),
+        Value::String(value) => value.trim().parse().unwrap_or_default(),
+        _ => 0,
+    })
+}
+
+/// Skips entries which aren't objects instead of dropping the whole sample.
+fn lenient_sample<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<PlayerSample>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Array(items) => items
+            .into_iter()
+            .filter(Value::is_object)
+            .filter_map(|item| serde_json::from_value(item).ok())
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_vanilla_response() {
+        let status = ServerStatus::parse(
+            r#"{
+                "version": { "name": "1.19.2", "protocol": 760 },
+                "players": {
+                    "max": 100,
+                    "online": 5,
+                    "sample": [{ "name": "thinkofdeath", "id": "4566e69f-c907-48ee-8d71-d7ba5aa00d20" }]
+                },
+                "description": { "text": "Hello world" },
+                "favicon": "data:image/png;base64,iVBORw0K",
+                "enforcesSecureChat": true
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(status.version.name, "1.19.2");
+        assert_eq!(status.version.protocol, 760);
+        assert_eq!((status.players.online, status.players.max), (5, 100));
+        assert_eq!(status.players.sample[0].name, "thinkofdeath");
+        assert_eq!(status.description.plain_text(), "Hello world");
+        assert_eq!(status.enforces_secure_chat, Some(true));
+        assert_eq!(status.previews_chat, None);
+    }
+
+    #[test]
+    fn descriptions_can_be_plain_strings() {
+        let status = ServerStatus::parse(r#"{ "description": "§6Minehut §7Network" }"#).unwrap();
+        assert_eq!(status.description.text, "§6Minehut §7Network");
+        assert_eq!(status.description.plain_text(), "Minehut Network");
+    }
+
+    #[test]
+    fn descriptions_can_be_nested_components() {
+        let status = ServerStatus::parse(
+            r#"{
+                "description": {
+                    "text": "Hello ",
+                    "color": "gold",
+                    "bold": true,
+                    "extra": [{ "text": "§aworld" }, "!"]
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(status.description.color.as_deref(), Some("gold"));
+        assert_eq!(status.description.bold, Some(true));
+        assert_eq!(status.description.extra.len(), 2);
+        assert_eq!(status.description.plain_text(), "Hello world!");
+
+        let status =
+            ServerStatus::parse(r#"{ "description": [{ "text": "A" }, { "text": "B" }] }"#)
+                .unwrap();
+        assert_eq!(status.description.plain_text(), "AB");
+    }
+
+    #[test]
+    fn missing_and_malformed_fields_fall_back_to_defaults() {
+        let status = ServerStatus::parse(r#"{ "players": { "max": "20", "online": 3 } }"#).unwrap();
+        assert_eq!((status.players.online, status.players.max), (3, 20));
+        assert!(status.players.sample.is_empty());
+        assert_eq!(status.description, ChatComponent::default());
+        assert_eq!(status.favicon, None);
+
+        let status = ServerStatus::parse(
+            r#"{ "version": "Velocity", "players": { "sample": ["not a player", { "name": "Steve" }] } }"#,
+        )
+        .unwrap();
+        assert_eq!(status.version, StatusVersion::default());
+        assert_eq!(status.players.sample.len(), 1);
+        assert_eq!(status.players.sample[0].name, "Steve");
+    }
+
+    #[test]
+    fn favicons_lose_their_data_url_prefix_and_line_breaks() {
+        let status =
+            ServerStatus::parse(r#"{ "favicon": "data:image/png;base64,iVBO\nRw0K" }"#).unwrap();
+        assert_eq!(status.favicon_base64().as_deref(), Some("iVBORw0K"));
+
+        let status = ServerStatus::parse(r#"{ "favicon": 42 }"#).unwrap();
+        assert_eq!(status.favicon, None);
+        assert_eq!(status.favicon_base64(), None);
+    }
+
+    #[test]
+    fn other_fields_are_kept() {
+        let status = ServerStatus::parse(r#"{ "forgeData": { "fmlNetworkVersion": 2 } }"#).unwrap();
+        assert_eq!(status.other["forgeData"]["fmlNetworkVersion"], 2);
+    }
+}
@@ -1,58 +1,97 @@
+use crate::minecraft_bot::{CancelToken, StatusError, StatusTimeouts};
+use crate::minecraft_srv::{SrvResolver, SystemSrvResolver};
+use crate::minecraft_status::ServerStatus;
 use mc_protocol::ProtocolVersion;
+use minehut_api::watch::watch;
+use serenity::futures::StreamExt;
+use std::sync::Arc;
 use std::time::Duration;
 
 const TARGET_IP: &'static str = "mh-prd.minehut.com";
-const TARGET_PORT: u16 = 25565;
+// left to the SRV record, like a player joining without a port
+const TARGET_PORT: Option<u16> = None;
 const NATIVE_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::V118R2;
+// a stuck query counts as a failed poll instead of holding up the ones after it
+const STATUS_TIMEOUTS: StatusTimeouts = StatusTimeouts {
+    connect: Duration::from_secs(3),
+    read: Duration::from_secs(3),
+    overall: Duration::from_secs(5),
+};
 
-#[derive(serde_derive::Deserialize)]
-pub struct StatusBreakdown {
-    #[serde(rename = "online")]
-    players: usize,
-    #[serde(rename = "max")]
-    servers: usize,
-}
-
-#[derive(serde_derive::Deserialize)]
-pub struct StatusResponseFull {
-    #[serde(rename = "players")]
-    breakdown: StatusBreakdown,
-}
-
-#[derive(Debug)]
 pub struct MinecraftStats {
     pub players: usize,
+    /// How many servers are online, which Minehut's proxy reports in place of its max players.
     pub servers: usize,
+    pub status: ServerStatus,
     pub latency: u128,
 }
 
-pub async fn query_minecraft_status() -> anyhow::Result<MinecraftStats> {
-    let bot_response =
-        crate::minecraft_bot::request_status(TARGET_IP, TARGET_PORT, NATIVE_PROTOCOL_VERSION)
-            .await?;
+// emitted every poll, so the favicon and sample are left out of the logs
+impl std::fmt::Debug for MinecraftStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MinecraftStats")
+            .field("version", &self.status.version.name)
+            .field("players", &self.players)
+            .field("servers", &self.servers)
+            .field("latency", &self.latency)
+            .finish()
+    }
+}
+
+pub async fn query_minecraft_status(
+    cancel: &CancelToken,
+    srv: Option<&dyn SrvResolver>,
+) -> anyhow::Result<MinecraftStats> {
+    let bot_response = crate::minecraft_bot::request_status_with(
+        TARGET_IP,
+        TARGET_PORT,
+        NATIVE_PROTOCOL_VERSION,
+        STATUS_TIMEOUTS,
+        cancel,
+        srv,
+    )
+    .await?;
     log::info!("Got response!");
-    let full_res =
-        serde_json::from_str::<StatusResponseFull>(bot_response.0.json_response.as_ref())?;
-    return Ok(MinecraftStats {
+    let status = ServerStatus::parse(bot_response.0.json_response.as_ref())?;
+    Ok(MinecraftStats {
+        players: status.players.online.max(0) as usize,
+        servers: status.players.max.max(0) as usize,
+        status,
         latency: bot_response.1,
-        players: full_res.breakdown.players,
-        servers: full_res.breakdown.servers,
-    });
+    })
 }
 
 crate::reporter!(Option<MinecraftStats>, "MinecraftStats", |self| {
     tokio::spawn(async move {
-        loop {
-            self.emit(match query_minecraft_status().await {
-                Ok(x) => Some(x),
+        // poll every half second, until the bot shuts down
+        let cancel = crate::shutdown_token();
+        let srv = match SystemSrvResolver::from_system_conf() {
+            Ok(resolver) => Some(Arc::new(resolver) as Arc<dyn SrvResolver>),
+            Err(err) => {
+                log::warn!("Failed to load the DNS configuration, skipping SRV lookups: {err:?}");
+                None
+            }
+        };
+        let mut stats = watch(Duration::from_millis(500), move || {
+            let cancel = cancel.clone();
+            let srv = srv.clone();
+            async move { query_minecraft_status(&cancel, srv.as_deref()).await }
+        });
+        while let Some(event) = stats.next().await {
+            self.emit(match event {
+                Ok(event) => Some(event.value),
                 Err(err) => {
-                    log::error!("Error calling minecraft status {err:?}");
+                    match err.downcast_ref::<StatusError>() {
+                        Some(StatusError::Cancelled) => break,
+                        Some(StatusError::TimedOut { .. }) => {
+                            log::warn!("Minecraft status request gave up: {err}")
+                        }
+                        None => log::error!("Error calling minecraft status {err:?}"),
+                    }
                     None
                 }
             })
             .await;
-            // poll every half second
-            tokio::time::sleep(Duration::from_millis(500)).await;
         }
     });
     Ok(())
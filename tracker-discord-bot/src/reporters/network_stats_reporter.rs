@@ -1,4 +1,5 @@
 use minehut_api::prelude::*;
+use serenity::futures::StreamExt;
 use std::time::Duration;
 
 crate::reporter!(
@@ -6,13 +7,10 @@ crate::reporter!(
     "NetworkStats",
     |self| {
         tokio::spawn(async move {
-            loop {
-                match get_simple_stats().await {
-                    Ok(stats) => self.emit(Some(stats)).await,
-                    Err(_) => self.emit(None).await,
-                };
-                // poll every half second
-                tokio::time::sleep(Duration::from_millis(500)).await;
+            // poll every half second
+            let mut stats = watch_simple_stats(Duration::from_millis(500));
+            while let Some(event) = stats.next().await {
+                self.emit(event.ok().map(|event| event.value)).await;
             }
         });
         Ok(())